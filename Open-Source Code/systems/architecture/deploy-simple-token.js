@@ -51,6 +51,10 @@ const CONFIG = {
     decimals: 9,
     transferFeeBasisPoints: 200, // 2% Native Tax
     maxTransferFee: BigInt(100_000_000) * BigInt(10 ** 9), // Effectively unlimited cap (10% of supply)
+
+    // grid_transfer_hook owns the fee authorities via PDA [b"fee-authority", mint],
+    // so the rate is DAO-governed (set_transfer_fee) and fees are harvested to the sweep vault
+    transferHookProgramId: '7Py52EPwuCxYJ7UiBKrk5ce14T4NTxuutHFtyoWDdqFV',
     metadataUri: 'https://raw.githubusercontent.com/SovereignSwarm/grid-terminal/main/docs/metadata.json',
     
     // Distribution
@@ -113,6 +117,10 @@ async function run() {
     const mintKeypair = Keypair.generate();
     const mint = mintKeypair.publicKey;
     const founderPubkey = new PublicKey(CONFIG.founderWallet);
+    const feeAuthority = PublicKey.findProgramAddressSync(
+        [Buffer.from('fee-authority'), mint.toBuffer()],
+        new PublicKey(CONFIG.transferHookProgramId)
+    )[0];
     
     // 1. Calculate Space and Rent
     const extensions = [
@@ -151,8 +159,8 @@ async function run() {
         // Initialize Transfer Fee (2%)
         createInitializeTransferFeeConfigInstruction(
             mint,
-            deployer.publicKey, // Authority to update fee
-            deployer.publicKey, // Authority to withdraw fee
+            feeAuthority, // Authority to update fee (hook PDA)
+            feeAuthority, // Authority to withdraw fee (hook PDA)
             CONFIG.transferFeeBasisPoints,
             CONFIG.maxTransferFee,
             TOKEN_2022_PROGRAM_ID
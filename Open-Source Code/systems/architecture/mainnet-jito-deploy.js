@@ -4,7 +4,7 @@
  * 
  * FEATURES:
  * - Token-2022 Standard
- * - 1% Transfer Fee (MEV-protected)
+ * - 2% Transfer Fee (1% burn / 1% ops, MEV-protected)
  * - On-chain Metadata
  * - Jito MEV Bundle Integration (Atomic Launch)
  * - Vault-based Security
@@ -53,8 +53,12 @@ const CONFIG = {
     name: 'The Grid',
     symbol: 'GRID',
     decimals: 9,
-    transferFeeBasisPoints: 100, // 1%
+    transferFeeBasisPoints: 200, // 2% (1% burn, 1% ops)
     maxTransferFee: BigInt(5000) * BigInt(10 ** 9), // 5000 GRID cap
+
+    // grid_transfer_hook owns the fee authorities via PDA [b"fee-authority", mint],
+    // so the rate is DAO-governed (set_transfer_fee) and fees are harvested to the sweep vault
+    transferHookProgramId: '7Py52EPwuCxYJ7UiBKrk5ce14T4NTxuutHFtyoWDdqFV',
     metadataUri: 'https://raw.githubusercontent.com/SovereignSwarm/grid-terminal/master/docs/metadata.json',
     
     // Distribution
@@ -140,6 +144,10 @@ async function run() {
     const mintKeypair = Keypair.generate();
     const mint = mintKeypair.publicKey;
     const founderPubkey = new PublicKey(CONFIG.founderWallet);
+    const feeAuthority = PublicKey.findProgramAddressSync(
+        [Buffer.from('fee-authority'), mint.toBuffer()],
+        new PublicKey(CONFIG.transferHookProgramId)
+    )[0];
     
     // 1. Calculate Space and Rent
     const extensions = [ExtensionType.TransferFeeConfig, ExtensionType.MetadataPointer];
@@ -174,8 +182,8 @@ async function run() {
         }),
         createInitializeTransferFeeConfigInstruction(
             mint,
-            deployer.publicKey,
-            deployer.publicKey,
+            feeAuthority, // Authority to update fee (hook PDA)
+            feeAuthority, // Authority to withdraw fee (hook PDA)
             CONFIG.transferFeeBasisPoints,
             CONFIG.maxTransferFee,
             TOKEN_2022_PROGRAM_ID
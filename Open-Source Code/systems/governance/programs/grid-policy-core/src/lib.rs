@@ -0,0 +1,909 @@
+//! Grid Policy Core: pure evaluation logic for the $GRID Policy Guard
+//!
+//! Linked on-chain by policy_guard and off-chain by agents, so both sides
+//! compute identical verdicts. No allocation, no Solana dependencies:
+//! callers take a snapshot of guard state and pass it in.
+
+#![no_std]
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Daily spend accumulator window
+pub const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+// Dynamic policy rule IDs
+/// Custom max transaction size as bps of treasury value
+pub const RULE_MAX_TX_SIZE: u8 = 1;
+/// RULE_03: max transaction size as bps of prior-month average revenue
+pub const RULE_REVENUE_CAP: u8 = 3;
+
+/// Accounting month length
+pub const MONTH_SECONDS: i64 = 30 * 24 * 60 * 60;
+/// Months kept in the revenue ring buffer
+pub const REVENUE_MONTHS: usize = 12;
+/// Completed months averaged for RULE_03
+pub const REVENUE_AVERAGE_MONTHS: u32 = 3;
+
+/// RULE_04: strategic buybacks per day, as bps of daily trading volume
+pub const BUYBACK_VOLUME_BPS: u64 = 200;
+
+/// Arithmetic overflow while computing a limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MathOverflow;
+
+pub type Result<T> = core::result::Result<T, MathOverflow>;
+
+/// `bps` basis points of `value`, rounded down
+pub fn bps_of(value: u64, bps: u64) -> Result<u64> {
+    let scaled = (value as u128)
+        .checked_mul(bps as u128)
+        .ok_or(MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(scaled).map_err(|_| MathOverflow)
+}
+
+// ============================================================================
+// KYA LEVELS
+// ============================================================================
+
+/// KYA Level requirements for operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KyaLevel {
+    Anonymous = 0,
+    Basic = 1,
+    Verified = 2,
+    Endorsed = 3,
+}
+
+/// Spending limits by KYA level (in basis points of treasury)
+impl KyaLevel {
+    pub fn from_u8(level: u8) -> Option<Self> {
+        match level {
+            0 => Some(KyaLevel::Anonymous),
+            1 => Some(KyaLevel::Basic),
+            2 => Some(KyaLevel::Verified),
+            3 => Some(KyaLevel::Endorsed),
+            _ => None,
+        }
+    }
+
+    pub fn daily_limit_bps(&self) -> u64 {
+        match self {
+            KyaLevel::Anonymous => 0,
+            KyaLevel::Basic => 10,     // 0.1%
+            KyaLevel::Verified => 100, // 1%
+            KyaLevel::Endorsed => 500, // 5%
+        }
+    }
+
+    pub fn per_tx_limit_bps(&self) -> u64 {
+        match self {
+            KyaLevel::Anonymous => 0,
+            KyaLevel::Basic => 1,      // 0.01%
+            KyaLevel::Verified => 10,  // 0.1%
+            KyaLevel::Endorsed => 100, // 1%
+        }
+    }
+}
+
+// ============================================================================
+// INPUTS
+// ============================================================================
+
+/// Guard state needed to evaluate a transaction
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GuardSnapshot {
+    pub paused: bool,
+    /// Whether the treasury valuation is recent enough to derive limits from
+    pub valuation_fresh: bool,
+    pub treasury_value: u64,
+    pub daily_spend_bps: u16,
+    pub max_drawdown_bps: u16,
+    pub cooldown_seconds: u32,
+    pub last_operation_ts: u64,
+    pub daily_spend_accumulator: u64,
+    pub last_reset_ts: i64,
+    /// Buybacks in the current daily window (RULE_04), tracked apart from spend
+    pub buyback_accumulator: u64,
+    /// Counterparty requirements, indexed by KYA level
+    pub counterparty_rules: [CounterpartyRule; 4],
+}
+
+/// Allowlist requirements for one KYA level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CounterpartyRule {
+    /// Destination owner must be allowlisted
+    pub allowlisted_destination_only: bool,
+    /// Target program must be allowlisted
+    pub allowlisted_program_only: bool,
+}
+
+/// DAO listing of a destination owner or target program
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Listing {
+    #[default]
+    Unlisted,
+    Allowed,
+    Denied,
+}
+
+/// A dynamic policy slot
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rule {
+    pub rule_id: u8,
+    pub threshold_bps: u16,
+    pub enabled: bool,
+}
+
+/// What the evaluated amount is for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Operation {
+    /// Ordinary treasury spend, charged to the daily limit (RULE_02)
+    #[default]
+    Spend,
+    /// Strategic buyback, charged to the volume cap (RULE_04) instead
+    Buyback,
+}
+
+/// The spend being evaluated
+#[derive(Clone, Copy, Debug)]
+pub struct Request {
+    pub amount: u64,
+    pub agent_kya_level: u8,
+    pub operation: Operation,
+    /// Listing of the destination owner, `None` if not supplied
+    pub destination: Option<Listing>,
+    /// Listing of the target program, `None` if not supplied
+    pub target_program: Option<Listing>,
+    pub now: i64,
+}
+
+/// External figures some rules depend on; `None` when not supplied or stale
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Feeds {
+    /// Prior-month average revenue in quote units (RULE_03)
+    pub revenue_average: Option<u64>,
+    /// Trailing 24h trading volume in quote units (RULE_04)
+    pub daily_volume: Option<u64>,
+}
+
+// ============================================================================
+// VERDICT
+// ============================================================================
+
+/// A rule that blocked (or would block) a transaction
+/// Declaration order is evaluation order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    CooldownActive,
+    GuardPaused,
+    ValuationStale,
+    InvalidKyaLevel,
+    InsufficientKyaLevel,
+    KyaLimitExceeded,
+    MaxDrawdownExceeded,
+    DailyLimitExceeded,
+    PolicyLimitExceeded,
+    RevenueTrackerRequired,
+    RevenueCapExceeded,
+    VolumeFeedUnavailable,
+    BuybackCapExceeded,
+    DestinationDenied,
+    ProgramDenied,
+    DestinationNotAllowlisted,
+    ProgramNotAllowlisted,
+}
+
+impl Violation {
+    pub const ALL: [Violation; 17] = [
+        Violation::CooldownActive,
+        Violation::GuardPaused,
+        Violation::ValuationStale,
+        Violation::InvalidKyaLevel,
+        Violation::InsufficientKyaLevel,
+        Violation::KyaLimitExceeded,
+        Violation::MaxDrawdownExceeded,
+        Violation::DailyLimitExceeded,
+        Violation::PolicyLimitExceeded,
+        Violation::RevenueTrackerRequired,
+        Violation::RevenueCapExceeded,
+        Violation::VolumeFeedUnavailable,
+        Violation::BuybackCapExceeded,
+        Violation::DestinationDenied,
+        Violation::ProgramDenied,
+        Violation::DestinationNotAllowlisted,
+        Violation::ProgramNotAllowlisted,
+    ];
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Set of violations, iterated in evaluation order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Violations(u32);
+
+impl Violations {
+    pub fn insert(&mut self, violation: Violation) {
+        self.0 |= violation.bit();
+    }
+
+    pub fn contains(&self, violation: Violation) -> bool {
+        self.0 & violation.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The violation an enforcing caller should report
+    pub fn first(&self) -> Option<Violation> {
+        self.iter().next()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Violation> + '_ {
+        Violation::ALL.into_iter().filter(|v| self.contains(*v))
+    }
+}
+
+/// Limits that applied to the evaluated amount
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EffectiveLimits {
+    /// Per-transaction limit for the agent's KYA level
+    pub kya_tx_limit: u64,
+    /// Global max drawdown per transaction
+    pub max_drawdown: u64,
+    /// Daily spend limit
+    pub daily_limit: u64,
+    /// Custom max tx size (RULE_MAX_TX_SIZE), if enabled
+    pub policy_tx_limit: Option<u64>,
+    /// Prior-month revenue cap (RULE_03), if enabled and computable
+    pub revenue_tx_limit: Option<u64>,
+    /// Buyback allowance left today (RULE_04), for buybacks with a fresh feed
+    pub buyback_headroom: Option<u64>,
+}
+
+/// Structured result of a policy evaluation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Verdict {
+    pub violations: Violations,
+    /// Daily spend still available before this transaction
+    pub daily_headroom: u64,
+    /// Seconds until the cooldown allows another operation
+    pub cooldown_remaining: u64,
+    pub limits: EffectiveLimits,
+    /// Listings the counterparty checks used
+    pub destination: Option<Listing>,
+    pub target_program: Option<Listing>,
+}
+
+impl Verdict {
+    pub fn allowed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// ============================================================================
+// EVALUATION
+// ============================================================================
+
+/// Evaluate every rule against a request without mutating anything
+pub fn evaluate(
+    guard: &GuardSnapshot,
+    policies: &[Rule],
+    feeds: &Feeds,
+    request: &Request,
+) -> Result<Verdict> {
+    let mut violations = Violations::default();
+    let amount = request.amount;
+    let now = request.now;
+
+    // 1. Cooldown
+    let unlock_ts = guard
+        .last_operation_ts
+        .checked_add(guard.cooldown_seconds as u64)
+        .ok_or(MathOverflow)?;
+    let cooldown_remaining = unlock_ts.saturating_add(1).saturating_sub(now.max(0) as u64);
+    if cooldown_remaining > 0 {
+        violations.insert(Violation::CooldownActive);
+    }
+
+    // 2. Pause state and valuation freshness
+    if guard.paused {
+        violations.insert(Violation::GuardPaused);
+    }
+    if !guard.valuation_fresh {
+        violations.insert(Violation::ValuationStale);
+    }
+
+    // 3. KYA level
+    let kya_level = KyaLevel::from_u8(request.agent_kya_level);
+    match kya_level {
+        None => violations.insert(Violation::InvalidKyaLevel),
+        Some(level) if level < KyaLevel::Basic => {
+            violations.insert(Violation::InsufficientKyaLevel)
+        }
+        Some(_) => {}
+    }
+
+    // 4. Per-transaction limit based on KYA level
+    let kya_tx_limit = match kya_level {
+        Some(level) => bps_of(guard.treasury_value, level.per_tx_limit_bps())?,
+        None => 0,
+    };
+    if amount > kya_tx_limit {
+        violations.insert(Violation::KyaLimitExceeded);
+    }
+
+    // 5. Global max drawdown
+    let max_drawdown = bps_of(guard.treasury_value, guard.max_drawdown_bps as u64)?;
+    if amount > max_drawdown {
+        violations.insert(Violation::MaxDrawdownExceeded);
+    }
+
+    // 6. Daily limit (accumulators reset every 24h): RULE_02 for spend,
+    //    RULE_04 for buybacks
+    let window_elapsed = daily_window_elapsed(guard.last_reset_ts, now);
+    let daily_limit = bps_of(guard.treasury_value, guard.daily_spend_bps as u64)?;
+    let spent_today = if window_elapsed { 0 } else { guard.daily_spend_accumulator };
+    let daily_headroom = daily_limit.saturating_sub(spent_today);
+    let mut buyback_headroom = None;
+    match request.operation {
+        Operation::Spend => {
+            if amount > daily_headroom {
+                violations.insert(Violation::DailyLimitExceeded);
+            }
+        }
+        Operation::Buyback => match feeds.daily_volume {
+            Some(volume) => {
+                let bought_today = if window_elapsed { 0 } else { guard.buyback_accumulator };
+                let headroom = bps_of(volume, BUYBACK_VOLUME_BPS)?.saturating_sub(bought_today);
+                buyback_headroom = Some(headroom);
+                if amount > headroom {
+                    violations.insert(Violation::BuybackCapExceeded);
+                }
+            }
+            None => violations.insert(Violation::VolumeFeedUnavailable),
+        },
+    }
+
+    // 7. Dynamic policies
+    let mut policy_tx_limit = None;
+    let mut revenue_tx_limit = None;
+    for policy in policies.iter().filter(|p| p.enabled) {
+        match policy.rule_id {
+            RULE_MAX_TX_SIZE => {
+                let limit = bps_of(guard.treasury_value, policy.threshold_bps as u64)?;
+                policy_tx_limit = Some(limit);
+                if amount > limit {
+                    violations.insert(Violation::PolicyLimitExceeded);
+                }
+            }
+            RULE_REVENUE_CAP => match feeds.revenue_average {
+                Some(average) => {
+                    let limit = bps_of(average, policy.threshold_bps as u64)?;
+                    revenue_tx_limit = Some(limit);
+                    if amount > limit {
+                        violations.insert(Violation::RevenueCapExceeded);
+                    }
+                }
+                None => violations.insert(Violation::RevenueTrackerRequired),
+            },
+            _ => {}
+        }
+    }
+
+    // 8. Counterparties: denylist always applies, allowlist per KYA level
+    let rule = kya_level
+        .map(|level| guard.counterparty_rules[level as usize])
+        .unwrap_or_default();
+    match request.destination {
+        Some(Listing::Denied) => violations.insert(Violation::DestinationDenied),
+        Some(Listing::Allowed) => {}
+        _ if rule.allowlisted_destination_only => {
+            violations.insert(Violation::DestinationNotAllowlisted)
+        }
+        _ => {}
+    }
+    match request.target_program {
+        Some(Listing::Denied) => violations.insert(Violation::ProgramDenied),
+        Some(Listing::Allowed) => {}
+        _ if rule.allowlisted_program_only => violations.insert(Violation::ProgramNotAllowlisted),
+        _ => {}
+    }
+
+    Ok(Verdict {
+        violations,
+        daily_headroom,
+        cooldown_remaining,
+        limits: EffectiveLimits {
+            kya_tx_limit,
+            max_drawdown,
+            daily_limit,
+            policy_tx_limit,
+            revenue_tx_limit,
+            buyback_headroom,
+        },
+        destination: request.destination,
+        target_program: request.target_program,
+    })
+}
+
+/// Whether the daily spend accumulator should be reset at `now`
+pub fn daily_window_elapsed(last_reset_ts: i64, now: i64) -> bool {
+    now.saturating_sub(last_reset_ts) >= DAY_SECONDS
+}
+
+// ============================================================================
+// REVENUE (RULE_03)
+// ============================================================================
+
+/// Accounting month containing `ts`
+pub fn month_index(ts: i64) -> Result<u32> {
+    u32::try_from(ts / MONTH_SECONDS).map_err(|_| MathOverflow)
+}
+
+/// Average revenue over the last completed months
+/// `buckets` holds `(month, amount)` pairs indexed by `month % REVENUE_MONTHS`.
+/// Months since creation with nothing recorded count as zero; with no
+/// completed month yet the average is zero, so RULE_03 fails closed.
+pub fn prior_month_average(
+    buckets: &[(u32, u64); REVENUE_MONTHS],
+    created_month: u32,
+    now: i64,
+) -> Result<u64> {
+    let current = month_index(now)?;
+    let months = current
+        .saturating_sub(created_month)
+        .min(REVENUE_AVERAGE_MONTHS);
+    if months == 0 {
+        return Ok(0);
+    }
+
+    let mut total: u64 = 0;
+    for month in (current - months)..current {
+        let (bucket_month, amount) = buckets[month as usize % REVENUE_MONTHS];
+        if bucket_month == month {
+            total = total.checked_add(amount).ok_or(MathOverflow)?;
+        }
+    }
+    Ok(total / months as u64)
+}
+
+// ============================================================================
+// PRICE FEEDS
+// Pyth v2 price accounts, shared by the guard's treasury valuation and the
+// transfer hook so both read and check feeds the same way.
+// ============================================================================
+
+/// Quote values (treasury value, guard limits) are USD with 6 decimals
+pub const QUOTE_DECIMALS: i32 = 6;
+
+/// Pyth v2 price account layout. Switchboard exposes the same layout for
+/// its Pyth-compatible feeds, so either can be used.
+pub mod price_feed_layout {
+    pub const MAGIC: u32 = 0xa1b2_c3d4;
+    pub const VERSION: u32 = 2;
+    pub const ACCOUNT_TYPE_PRICE: u32 = 3;
+    pub const STATUS_TRADING: u32 = 1;
+
+    pub const MAGIC_OFFSET: usize = 0;
+    pub const VERSION_OFFSET: usize = 4;
+    pub const ACCOUNT_TYPE_OFFSET: usize = 8;
+    pub const EXPO_OFFSET: usize = 20;
+    pub const TIMESTAMP_OFFSET: usize = 96;
+    pub const PRICE_OFFSET: usize = 208;
+    pub const CONF_OFFSET: usize = 216;
+    pub const STATUS_OFFSET: usize = 224;
+    pub const MIN_LEN: usize = 240;
+}
+
+/// Why a price feed cannot be used
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceError {
+    /// Not a Pyth v2 price account
+    InvalidFeed,
+    /// Not trading, or no positive price
+    Unavailable,
+    /// Published longer ago than the tolerance allows
+    Stale,
+    /// Confidence interval wider than the tolerance allows
+    ConfidenceTooWide,
+    Overflow,
+}
+
+/// Staleness and confidence limits a feed must meet
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PriceTolerance {
+    /// Maximum age of the feed's publish time
+    pub max_staleness_seconds: u32,
+    /// Maximum confidence interval as bps of price
+    pub max_confidence_bps: u16,
+}
+
+/// Aggregate price read from a feed account
+#[derive(Clone, Copy, Debug)]
+pub struct PriceFeedData {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub status: u32,
+    pub publish_ts: i64,
+}
+
+impl PriceFeedData {
+    /// Read the aggregate price from a feed account's data
+    pub fn parse(data: &[u8]) -> core::result::Result<Self, PriceError> {
+        use price_feed_layout::*;
+        if data.len() < MIN_LEN {
+            return Err(PriceError::InvalidFeed);
+        }
+        let u32_at = |o: usize| u32::from_le_bytes(data[o..o + 4].try_into().unwrap());
+        let u64_at = |o: usize| u64::from_le_bytes(data[o..o + 8].try_into().unwrap());
+
+        if u32_at(MAGIC_OFFSET) != MAGIC
+            || u32_at(VERSION_OFFSET) != VERSION
+            || u32_at(ACCOUNT_TYPE_OFFSET) != ACCOUNT_TYPE_PRICE
+        {
+            return Err(PriceError::InvalidFeed);
+        }
+        Ok(PriceFeedData {
+            price: u64_at(PRICE_OFFSET) as i64,
+            conf: u64_at(CONF_OFFSET),
+            expo: u32_at(EXPO_OFFSET) as i32,
+            status: u32_at(STATUS_OFFSET),
+            publish_ts: u64_at(TIMESTAMP_OFFSET) as i64,
+        })
+    }
+
+    /// Lower bound of the confidence interval, after staleness and
+    /// confidence checks. Valuing holdings at `price - conf` keeps limits
+    /// conservative.
+    pub fn conservative_price(&self, tolerance: &PriceTolerance, now: i64) -> core::result::Result<u64, PriceError> {
+        let price = self.checked_price(tolerance, now)?;
+        price.checked_sub(self.conf).ok_or(PriceError::ConfidenceTooWide)
+    }
+
+    /// Upper bound of the confidence interval, used to value outgoing spend
+    pub fn spend_price(&self, tolerance: &PriceTolerance, now: i64) -> core::result::Result<u64, PriceError> {
+        let price = self.checked_price(tolerance, now)?;
+        price.checked_add(self.conf).ok_or(PriceError::Overflow)
+    }
+
+    fn checked_price(&self, tolerance: &PriceTolerance, now: i64) -> core::result::Result<u64, PriceError> {
+        if self.status != price_feed_layout::STATUS_TRADING || self.price <= 0 {
+            return Err(PriceError::Unavailable);
+        }
+        if now.saturating_sub(self.publish_ts) > tolerance.max_staleness_seconds as i64 {
+            return Err(PriceError::Stale);
+        }
+        let price = self.price as u64;
+        if self.conf as u128 * BPS_DENOMINATOR as u128 > price as u128 * tolerance.max_confidence_bps as u128 {
+            return Err(PriceError::ConfidenceTooWide);
+        }
+        Ok(price)
+    }
+
+    /// Encode a feed in the Pyth v2 layout
+    /// Lets local tests (e.g. solana-program-test) inject a mock feed.
+    #[cfg(any(test, feature = "mock-oracle"))]
+    pub fn to_account_data(&self) -> [u8; price_feed_layout::MIN_LEN] {
+        use price_feed_layout::*;
+        let mut data = [0u8; MIN_LEN];
+        data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&MAGIC.to_le_bytes());
+        data[VERSION_OFFSET..VERSION_OFFSET + 4].copy_from_slice(&VERSION.to_le_bytes());
+        data[ACCOUNT_TYPE_OFFSET..ACCOUNT_TYPE_OFFSET + 4].copy_from_slice(&ACCOUNT_TYPE_PRICE.to_le_bytes());
+        data[EXPO_OFFSET..EXPO_OFFSET + 4].copy_from_slice(&self.expo.to_le_bytes());
+        data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&self.publish_ts.to_le_bytes());
+        data[PRICE_OFFSET..PRICE_OFFSET + 8].copy_from_slice(&self.price.to_le_bytes());
+        data[CONF_OFFSET..CONF_OFFSET + 8].copy_from_slice(&self.conf.to_le_bytes());
+        data[STATUS_OFFSET..STATUS_OFFSET + 4].copy_from_slice(&self.status.to_le_bytes());
+        data
+    }
+}
+
+/// Value `amount` base units (`decimals`) at `price * 10^expo` USD per whole
+/// token, in USD with `quote_decimals` decimals, rounded down
+pub fn usd_value(amount: u64, decimals: u8, price: u64, expo: i32, quote_decimals: i32) -> Result<u64> {
+    let raw = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(MathOverflow)?;
+    let scale = expo + quote_decimals - decimals as i32;
+    let value = if scale >= 0 {
+        raw.checked_mul(10u128.checked_pow(scale as u32).ok_or(MathOverflow)?)
+            .ok_or(MathOverflow)?
+    } else {
+        match 10u128.checked_pow(scale.unsigned_abs()) {
+            Some(divisor) => raw / divisor,
+            None => 0,
+        }
+    };
+    u64::try_from(value).map_err(|_| MathOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1,000 quote units of treasury (6 decimals): Basic may move 100_000
+    /// per transaction, the daily limit is 5_000_000
+    const TREASURY: u64 = 1_000_000_000;
+    const NOW: i64 = 1_000;
+
+    fn guard() -> GuardSnapshot {
+        GuardSnapshot {
+            valuation_fresh: true,
+            treasury_value: TREASURY,
+            daily_spend_bps: 50,
+            max_drawdown_bps: 500,
+            ..Default::default()
+        }
+    }
+
+    fn request(amount: u64, agent_kya_level: u8) -> Request {
+        Request {
+            amount,
+            agent_kya_level,
+            operation: Operation::Spend,
+            destination: None,
+            target_program: None,
+            now: NOW,
+        }
+    }
+
+    fn rule(rule_id: u8, threshold_bps: u16) -> Rule {
+        Rule { rule_id, threshold_bps, enabled: true }
+    }
+
+    fn set(violations: &[Violation]) -> Violations {
+        let mut set = Violations::default();
+        violations.iter().for_each(|v| set.insert(*v));
+        set
+    }
+
+    fn violations(guard: &GuardSnapshot, policies: &[Rule], feeds: &Feeds, request: &Request) -> Violations {
+        evaluate(guard, policies, feeds, request).unwrap().violations
+    }
+
+    #[test]
+    fn spend_within_every_limit_is_allowed() {
+        let verdict = evaluate(&guard(), &[], &Feeds::default(), &request(100_000, 1)).unwrap();
+        assert!(verdict.allowed());
+        assert_eq!(verdict.daily_headroom, 5_000_000);
+        assert_eq!(verdict.cooldown_remaining, 0);
+        assert_eq!(verdict.limits.kya_tx_limit, 100_000);
+        assert_eq!(verdict.limits.max_drawdown, 50_000_000);
+        assert_eq!(verdict.limits.daily_limit, 5_000_000);
+    }
+
+    #[test]
+    fn each_rule_reports_its_violation() {
+        let none = Feeds::default();
+
+        let cooling = GuardSnapshot { cooldown_seconds: 3_600, last_operation_ts: 500, ..guard() };
+        assert_eq!(violations(&cooling, &[], &none, &request(1, 1)), set(&[Violation::CooldownActive]));
+
+        let paused = GuardSnapshot { paused: true, ..guard() };
+        assert_eq!(violations(&paused, &[], &none, &request(1, 1)), set(&[Violation::GuardPaused]));
+
+        let stale = GuardSnapshot { valuation_fresh: false, ..guard() };
+        assert_eq!(violations(&stale, &[], &none, &request(1, 1)), set(&[Violation::ValuationStale]));
+
+        assert_eq!(
+            violations(&guard(), &[], &none, &request(1, 4)),
+            set(&[Violation::InvalidKyaLevel, Violation::KyaLimitExceeded])
+        );
+        assert_eq!(
+            violations(&guard(), &[], &none, &request(1, 0)),
+            set(&[Violation::InsufficientKyaLevel, Violation::KyaLimitExceeded])
+        );
+        assert_eq!(violations(&guard(), &[], &none, &request(100_001, 1)), set(&[Violation::KyaLimitExceeded]));
+
+        let tight = GuardSnapshot { max_drawdown_bps: 1, ..guard() };
+        assert_eq!(violations(&tight, &[], &none, &request(100_001, 3)), set(&[Violation::MaxDrawdownExceeded]));
+
+        let spent = GuardSnapshot { daily_spend_accumulator: 4_950_000, ..guard() };
+        assert_eq!(violations(&spent, &[], &none, &request(50_001, 1)), set(&[Violation::DailyLimitExceeded]));
+
+        let max_tx = [rule(RULE_MAX_TX_SIZE, 1)];
+        assert_eq!(violations(&guard(), &max_tx, &none, &request(100_001, 2)), set(&[Violation::PolicyLimitExceeded]));
+
+        let revenue_cap = [rule(RULE_REVENUE_CAP, 500)];
+        assert_eq!(violations(&guard(), &revenue_cap, &none, &request(1, 1)), set(&[Violation::RevenueTrackerRequired]));
+        let revenue = Feeds { revenue_average: Some(1_000_000), ..none };
+        assert_eq!(violations(&guard(), &revenue_cap, &revenue, &request(50_000, 1)), set(&[]));
+        assert_eq!(violations(&guard(), &revenue_cap, &revenue, &request(50_001, 1)), set(&[Violation::RevenueCapExceeded]));
+
+        let buyback = Request { operation: Operation::Buyback, ..request(20_001, 1) };
+        assert_eq!(violations(&guard(), &[], &none, &buyback), set(&[Violation::VolumeFeedUnavailable]));
+        let volume = Feeds { daily_volume: Some(1_000_000), ..none };
+        assert_eq!(violations(&guard(), &[], &volume, &buyback), set(&[Violation::BuybackCapExceeded]));
+
+        let denied = Request {
+            destination: Some(Listing::Denied),
+            target_program: Some(Listing::Denied),
+            ..request(1, 1)
+        };
+        assert_eq!(
+            violations(&guard(), &[], &none, &denied),
+            set(&[Violation::DestinationDenied, Violation::ProgramDenied])
+        );
+
+        let mut allowlist_only = guard();
+        allowlist_only.counterparty_rules[KyaLevel::Basic as usize] = CounterpartyRule {
+            allowlisted_destination_only: true,
+            allowlisted_program_only: true,
+        };
+        assert_eq!(
+            violations(&allowlist_only, &[], &none, &request(1, 1)),
+            set(&[Violation::DestinationNotAllowlisted, Violation::ProgramNotAllowlisted])
+        );
+        let listed = Request {
+            destination: Some(Listing::Allowed),
+            target_program: Some(Listing::Allowed),
+            ..request(1, 1)
+        };
+        assert_eq!(violations(&allowlist_only, &[], &none, &listed), set(&[]));
+        // The allowlist rule of another level does not apply
+        assert_eq!(violations(&allowlist_only, &[], &none, &request(1, 2)), set(&[]));
+    }
+
+    #[test]
+    fn violations_iterate_in_declaration_order() {
+        for (index, violation) in Violation::ALL.iter().enumerate() {
+            assert_eq!(*violation as usize, index);
+        }
+
+        let everything = GuardSnapshot {
+            paused: true,
+            valuation_fresh: false,
+            cooldown_seconds: 3_600,
+            last_operation_ts: 500,
+            ..guard()
+        };
+        let denied = Request { destination: Some(Listing::Denied), ..request(1, 1) };
+        let found = violations(&everything, &[], &Feeds::default(), &denied);
+        assert_eq!(found.first(), Some(Violation::CooldownActive));
+
+        let mut order = found.iter();
+        assert_eq!(order.next(), Some(Violation::CooldownActive));
+        assert_eq!(order.next(), Some(Violation::GuardPaused));
+        assert_eq!(order.next(), Some(Violation::ValuationStale));
+        assert_eq!(order.next(), Some(Violation::DestinationDenied));
+        assert_eq!(order.next(), None);
+    }
+
+    #[test]
+    fn per_transaction_limit_scales_with_kya_level() {
+        for level in [KyaLevel::Anonymous, KyaLevel::Basic, KyaLevel::Verified, KyaLevel::Endorsed] {
+            let limit = TREASURY * level.per_tx_limit_bps() / BPS_DENOMINATOR;
+            let at_limit = evaluate(&guard(), &[], &Feeds::default(), &request(limit, level as u8)).unwrap();
+            assert_eq!(at_limit.limits.kya_tx_limit, limit);
+            assert!(!at_limit.violations.contains(Violation::KyaLimitExceeded));
+
+            let over = violations(&guard(), &[], &Feeds::default(), &request(limit + 1, level as u8));
+            assert!(over.contains(Violation::KyaLimitExceeded));
+        }
+        assert_eq!(KyaLevel::from_u8(4), None);
+    }
+
+    #[test]
+    fn daily_window_resets_the_accumulators() {
+        let spent = GuardSnapshot {
+            daily_spend_accumulator: 5_000_000,
+            buyback_accumulator: 20_000,
+            ..guard()
+        };
+        let volume = Feeds { daily_volume: Some(1_000_000), ..Default::default() };
+        let buyback = Request { operation: Operation::Buyback, ..request(1, 1) };
+        assert_eq!(violations(&spent, &[], &volume, &request(1, 1)), set(&[Violation::DailyLimitExceeded]));
+        assert_eq!(violations(&spent, &[], &volume, &buyback), set(&[Violation::BuybackCapExceeded]));
+
+        let next_day = Request { now: DAY_SECONDS, ..request(1, 1) };
+        let verdict = evaluate(&spent, &[], &volume, &next_day).unwrap();
+        assert!(verdict.allowed());
+        assert_eq!(verdict.daily_headroom, 5_000_000);
+        let verdict = evaluate(&spent, &[], &volume, &Request { operation: Operation::Buyback, ..next_day }).unwrap();
+        assert_eq!(verdict.limits.buyback_headroom, Some(20_000));
+    }
+
+    #[test]
+    fn daily_window_boundaries() {
+        assert!(!daily_window_elapsed(0, DAY_SECONDS - 1));
+        assert!(daily_window_elapsed(0, DAY_SECONDS));
+        assert!(daily_window_elapsed(100, 100 + DAY_SECONDS));
+        // A reset in the future (clock skew) never elapses
+        assert!(!daily_window_elapsed(DAY_SECONDS, 0));
+        // Never reset
+        assert!(daily_window_elapsed(i64::MIN, 0));
+        assert!(!daily_window_elapsed(i64::MAX, i64::MIN));
+    }
+
+    #[test]
+    fn bps_of_rounds_down_and_reports_overflow() {
+        assert_eq!(bps_of(9_999, 1), Ok(0));
+        assert_eq!(bps_of(TREASURY, 50), Ok(5_000_000));
+        assert_eq!(bps_of(u64::MAX, BPS_DENOMINATOR), Ok(u64::MAX));
+        assert_eq!(bps_of(u64::MAX, BPS_DENOMINATOR + 1), Err(MathOverflow));
+        assert_eq!(bps_of(u64::MAX, u64::MAX), Err(MathOverflow));
+
+        let huge = GuardSnapshot { treasury_value: u64::MAX, daily_spend_bps: u16::MAX, ..guard() };
+        assert_eq!(evaluate(&huge, &[], &Feeds::default(), &request(1, 1)), Err(MathOverflow));
+    }
+
+    #[test]
+    fn revenue_average_covers_the_last_completed_months() {
+        let mut buckets = [(0u32, 0u64); REVENUE_MONTHS];
+        for month in 0..6u32 {
+            buckets[month as usize % REVENUE_MONTHS] = (month, (month as u64 + 1) * 1_000);
+        }
+        let month_start = |month: i64| month * MONTH_SECONDS;
+
+        // Nothing completed yet: fails closed
+        assert_eq!(prior_month_average(&buckets, 5, month_start(5) + 10), Ok(0));
+        // One completed month since creation averages over one month
+        assert_eq!(prior_month_average(&buckets, 4, month_start(5)), Ok(5_000));
+        // Later on, only the last REVENUE_AVERAGE_MONTHS count
+        assert_eq!(prior_month_average(&buckets, 0, month_start(6) - 1), Ok((3_000 + 4_000 + 5_000) / 3));
+        assert_eq!(prior_month_average(&buckets, 0, month_start(6)), Ok((4_000 + 5_000 + 6_000) / 3));
+
+        // Buckets still holding last year's months count as zero
+        let next_year = month_start(6 + REVENUE_MONTHS as i64);
+        assert_eq!(prior_month_average(&buckets, 0, next_year), Ok(0));
+        buckets[5] = (5 + REVENUE_MONTHS as u32, 9_000);
+        assert_eq!(prior_month_average(&buckets, 0, next_year), Ok(9_000 / 3));
+    }
+
+    fn feed(price: i64, conf: u64, expo: i32, publish_ts: i64) -> PriceFeedData {
+        PriceFeedData { price, conf, expo, status: price_feed_layout::STATUS_TRADING, publish_ts }
+    }
+
+    const TOLERANCE: PriceTolerance = PriceTolerance { max_staleness_seconds: 60, max_confidence_bps: 100 };
+
+    #[test]
+    fn price_feed_round_trips_through_the_account_layout() {
+        let data = feed(250, 1, -2, 7).to_account_data();
+        let parsed = PriceFeedData::parse(&data).unwrap();
+        assert_eq!((parsed.price, parsed.conf, parsed.expo, parsed.publish_ts), (250, 1, -2, 7));
+
+        assert_eq!(PriceFeedData::parse(&data[..16]).unwrap_err(), PriceError::InvalidFeed);
+        let mut wrong_type = data;
+        wrong_type[price_feed_layout::ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(PriceFeedData::parse(&wrong_type).unwrap_err(), PriceError::InvalidFeed);
+    }
+
+    #[test]
+    fn price_bounds_follow_the_confidence_interval() {
+        // $1.00 +/- $0.01 (100 bps, the tolerated maximum)
+        let quoted = feed(100, 1, -2, 0);
+        assert_eq!(quoted.conservative_price(&TOLERANCE, 0).unwrap(), 99);
+        assert_eq!(quoted.spend_price(&TOLERANCE, 0).unwrap(), 101);
+        assert_eq!(feed(100, 2, -2, 0).spend_price(&TOLERANCE, 0).unwrap_err(), PriceError::ConfidenceTooWide);
+    }
+
+    #[test]
+    fn stale_or_halted_feeds_are_rejected() {
+        assert!(feed(100, 0, -2, 0).spend_price(&TOLERANCE, 60).is_ok());
+        assert_eq!(feed(100, 0, -2, 0).spend_price(&TOLERANCE, 61).unwrap_err(), PriceError::Stale);
+        assert_eq!(feed(0, 0, -2, 0).spend_price(&TOLERANCE, 0).unwrap_err(), PriceError::Unavailable);
+        let halted = PriceFeedData { status: 0, ..feed(100, 0, -2, 0) };
+        assert_eq!(halted.conservative_price(&TOLERANCE, 0).unwrap_err(), PriceError::Unavailable);
+    }
+
+    #[test]
+    fn usd_value_scales_by_exponent_and_decimals() {
+        // 1.5 tokens (6 decimals) at $2.00000000
+        assert_eq!(usd_value(1_500_000, 6, 200_000_000, -8, 0).unwrap(), 3);
+        assert_eq!(usd_value(1_500_000, 6, 200_000_000, -8, QUOTE_DECIMALS).unwrap(), 3_000_000);
+        // Sub-dollar values round down, but still count in quote units
+        assert_eq!(usd_value(1, 6, 200_000_000, -8, 0).unwrap(), 0);
+        assert_eq!(usd_value(1, 6, 200_000_000, -8, QUOTE_DECIMALS).unwrap(), 2);
+        // Positive exponent: 3 tokens at 4 * 10^2
+        assert_eq!(usd_value(3, 0, 4, 2, 0).unwrap(), 1_200);
+        assert_eq!(usd_value(u64::MAX, 0, i64::MAX as u64, 0, 0).unwrap_err(), MathOverflow);
+    }
+}
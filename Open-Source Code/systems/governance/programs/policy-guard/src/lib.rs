@@ -1,18 +1,47 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::memo::{build_memo, BuildMemo, Memo};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use agent_identity::AgentIdentity;
+use grid_policy_core as core;
+
+pub use grid_policy_core::{
+    price_feed_layout, KyaLevel, PriceFeedData, PriceTolerance, BUYBACK_VOLUME_BPS, MONTH_SECONDS,
+    QUOTE_DECIMALS, REVENUE_AVERAGE_MONTHS, REVENUE_MONTHS, RULE_MAX_TX_SIZE, RULE_REVENUE_CAP,
+};
 
 declare_id!("2u4LtXMzdttMEV54jFEWnQrvCbx3TguyvWyfdho7MFRW");
 
 /// Policy Guard: Constitutional Enforcement for $GRID Treasury
 /// 
 /// This program acts as a middleware filter for treasury transactions,
-/// enforcing on-chain rules defined by the DAO.
+/// enforcing on-chain rules defined by the DAO. Each treasury or mint gets
+/// its own guard instance at `[b"guard", scope]`, created through the registry.
+/// Treasury funds sit in a guard-owned vault and only leave through
+/// `execute_treasury_transfer`, which enforces the rules below.
 /// 
 /// Rules implemented:
 /// - RULE_01: vCPI prevention (no proxy calls)
 /// - RULE_02: Daily operations limited to 0.5% treasury (auto)
 /// - RULE_03: No single transaction > 5% of prior-month average revenue
-/// - RULE_04: Strategic buybacks ≤ 2% of daily volume (oracle needed)
+/// - RULE_04: Strategic buybacks ≤ 2% of daily volume (reported volume feed)
 /// - RULE_05: 4-hour cooldown between treasury operations
+/// - Law 2: betting pool exposure ≤ 5% of treasury per pool, plus an aggregate cap
+/// 
+/// Evaluations also check the destination owner and target program against
+/// DAO-managed allow/deny lists, with allowlist requirements per KYA level.
+/// 
+/// Parameter changes are M-of-N admin proposals queued behind the Law 1
+/// timelock (72h, floor 24h). Pause remains instant for any admin or
+/// guardian, and the circuit breaker (Law 2) pauses
+/// automatically on repeated denials, spend velocity or a valuation drop.
 /// 
 /// Status: READY FOR AUDIT
 
@@ -20,379 +49,3095 @@ declare_id!("2u4LtXMzdttMEV54jFEWnQrvCbx3TguyvWyfdho7MFRW");
 pub mod policy_guard {
     use super::*;
 
-    /// Initialize the guard with DAO as admin
-    pub fn initialize_guard(ctx: Context<InitializeGuard>, config: GuardConfig) -> Result<()> {
+    /// Create the guard registry (program upgrade authority only)
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>, authority: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.authority = authority;
+        registry.guard_count = 0;
+        registry.bump = ctx.bumps.registry;
+        msg!("Guard registry initialized. Authority: {}", authority);
+        Ok(())
+    }
+
+    /// Hand guard creation rights to a new authority (registry authority only)
+    pub fn set_registry_authority(ctx: Context<SetRegistryAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.registry.authority = new_authority;
+        msg!("Guard registry authority: {}", new_authority);
+        Ok(())
+    }
+
+    /// Initialize a guard for `scope` (a treasury or a mint) with the DAO
+    /// admin set and pause guardians (registry authority only)
+    pub fn initialize_guard(ctx: Context<InitializeGuard>, scope: Pubkey, config: GuardConfig) -> Result<()> {
+        require!(config.admins.len() <= MAX_ADMINS, ErrorCode::AdminStorageFull);
+        require!(config.guardians.len() <= MAX_GUARDIANS, ErrorCode::GuardianStorageFull);
+
+        let registry = &mut ctx.accounts.registry;
+        registry.guard_count = registry.guard_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
         let guard = &mut ctx.accounts.guard_state;
-        guard.admin = config.admin;
+        guard.scope = scope;
+        guard.bump = ctx.bumps.guard_state;
+        guard.admins = [Pubkey::default(); MAX_ADMINS];
+        guard.admins[..config.admins.len()].copy_from_slice(&config.admins);
+        validate_admin_set(&guard.admins, config.admin_threshold)?;
+        guard.admin_threshold = config.admin_threshold;
+        guard.admin_set_version = 0;
+        guard.guardians = [Pubkey::default(); MAX_GUARDIANS];
+        guard.guardians[..config.guardians.len()].copy_from_slice(&config.guardians);
+
         guard.paused = false;
-        guard.daily_spend_bps = 50; // 0.5%
-        guard.max_drawdown_bps = 500; // 5%
-        guard.cooldown_seconds = 14400; // 4 hours
+        guard.agent_identity_program = agent_identity::ID;
+        guard.daily_spend_bps = DEFAULT_DAILY_SPEND_BPS;
+        guard.max_drawdown_bps = DEFAULT_MAX_DRAWDOWN_BPS;
+        guard.cooldown_seconds = DEFAULT_COOLDOWN_SECONDS;
+        validate_cooldown(guard.cooldown_seconds)?;
         guard.last_operation_ts = 0;
         guard.daily_spend_accumulator = 0;
         guard.last_reset_ts = Clock::get()?.unix_timestamp;
-        guard.treasury_value = 0; // Must be updated via update_treasury_stats
+        guard.treasury_value = 0; // Must be computed via refresh_treasury_value
+        guard.treasury_value_updated_ts = 0;
+        guard.treasury_assets = [TreasuryAsset::default(); MAX_TREASURY_ASSETS];
+        guard.timelock_seconds = DEFAULT_TIMELOCK_SECONDS;
+        guard.change_count = 0;
+        guard.breaker_config = CircuitBreakerConfig::DEFAULT;
+        guard.breaker = CircuitBreakerState::default();
+        guard.pool_exposure_bps = DEFAULT_POOL_EXPOSURE_BPS;
+        guard.open_exposure = 0;
         
         // Initialize policies with default (disabled) rules
         guard.policies = [PolicyRule::default(); 8];
         
-        msg!("Policy Guard initialized. Admin: {}", config.admin);
+        msg!(
+            "Policy Guard initialized for scope {}. Admins: {}-of-{}, guardians: {}",
+            scope,
+            config.admin_threshold,
+            config.admins.len(),
+            config.guardians.len()
+        );
+        emit!(GuardInitialized {
+            guard: guard.key(),
+            scope,
+            admins: config.admins,
+            admin_threshold: config.admin_threshold,
+            guardians: config.guardians,
+        });
         Ok(())
     }
 
-    /// Update Treasury Stats (Required for limit calculations)
-    pub fn update_treasury_stats(ctx: Context<UpdateStats>, total_value: u64) -> Result<()> {
+    /// Propose a guard change (admins only); the proposer's approval is counted
+    /// Timelocked changes need `eta` at least `timelock_seconds` in the
+    /// future (Law 1); `eta` is ignored for Unpause.
+    pub fn queue_change(ctx: Context<QueueChange>, change: GuardChange, eta: i64) -> Result<()> {
         let guard = &mut ctx.accounts.guard_state;
-        guard.treasury_value = total_value;
-        msg!("Treasury value updated: {}", total_value);
+        let proposer = ctx.accounts.proposer.key();
+        let admin_slot = guard.admin_index(&proposer).ok_or(ErrorCode::NotAnAdmin)?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let eta = queue_eta(guard, &change, eta, now)?;
+        validate_change(&change)?;
+
+        let pending = &mut ctx.accounts.pending_change;
+        pending.guard = guard.key();
+        pending.change_id = guard.change_count;
+        pending.change = change;
+        pending.queued_at = now;
+        pending.eta = eta;
+        pending.proposer = proposer;
+        pending.admin_set_version = guard.admin_set_version;
+        pending.approvals = 1 << admin_slot;
+        pending.rejections = 0;
+        pending.bump = ctx.bumps.pending_change;
+
+        guard.change_count = guard.change_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Change #{} queued by {}: {:?}. ETA: {}", pending.change_id, proposer, change, eta);
+        emit!(ChangeQueued {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            proposer,
+            change,
+            eta,
+        });
+        Ok(())
+    }
+
+    /// Approve a queued change (admins only)
+    pub fn approve_change(ctx: Context<VoteOnChange>) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let pending = &mut ctx.accounts.pending_change;
+        let admin_slot = guard.admin_index(&ctx.accounts.admin.key()).ok_or(ErrorCode::NotAnAdmin)?;
+        require!(pending.admin_set_version == guard.admin_set_version, ErrorCode::StaleAdminSet);
+
+        pending.approvals |= 1 << admin_slot;
+        pending.rejections &= !(1 << admin_slot);
+
+        msg!(
+            "Change #{} approvals: {} / {}",
+            pending.change_id,
+            pending.approvals.count_ones(),
+            guard.admin_threshold
+        );
+        emit!(ChangeVoted {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            admin: ctx.accounts.admin.key(),
+            approve: true,
+            approvals: pending.approvals.count_ones() as u8,
+            rejections: pending.rejections.count_ones() as u8,
+        });
+        Ok(())
+    }
+
+    /// Reject a queued change (admins only)
+    /// Once enough admins reject that approval is impossible, anyone may cancel.
+    pub fn reject_change(ctx: Context<VoteOnChange>) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let pending = &mut ctx.accounts.pending_change;
+        let admin_slot = guard.admin_index(&ctx.accounts.admin.key()).ok_or(ErrorCode::NotAnAdmin)?;
+        require!(pending.admin_set_version == guard.admin_set_version, ErrorCode::StaleAdminSet);
+
+        pending.rejections |= 1 << admin_slot;
+        pending.approvals &= !(1 << admin_slot);
+
+        msg!("Change #{} rejections: {}", pending.change_id, pending.rejections.count_ones());
+        emit!(ChangeVoted {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            admin: ctx.accounts.admin.key(),
+            approve: false,
+            approvals: pending.approvals.count_ones() as u8,
+            rejections: pending.rejections.count_ones() as u8,
+        });
         Ok(())
     }
 
-    /// Add or Update a policy rule
-    pub fn add_policy(ctx: Context<ManagePolicy>, rule: PolicyRule) -> Result<()> {
+    /// Apply a queued change once approved and its timelock has elapsed
+    /// Permissionless: the change was authorised by the admin quorum.
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
         let guard = &mut ctx.accounts.guard_state;
-        
-        // Find existing rule to update or empty slot
-        let mut updated = false;
-        
-        // 1. Try to update existing rule with same ID
-        for i in 0..guard.policies.len() {
-            if guard.policies[i].rule_id == rule.rule_id && guard.policies[i].rule_id != 0 {
-                guard.policies[i] = rule;
-                updated = true;
-                msg!("Updated Policy Rule ID: {}", rule.rule_id);
-                break;
+        let now = Clock::get()?.unix_timestamp;
+
+        check_executable(guard, pending, now)?;
+        let change = pending.change;
+        apply_change(guard, change, now)?;
+
+        msg!("Change #{} executed", pending.change_id);
+        emit!(ChangeExecuted {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            change,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Apply a queued `SetCounterpartyListing`, creating its listing PDA
+    /// Permissionless like execute_change; the caller pays the listing rent.
+    pub fn execute_listing_change(ctx: Context<ExecuteListingChange>, address: Pubkey) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+        let guard = &ctx.accounts.guard_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        check_executable(guard, pending, now)?;
+        let change = pending.change;
+        let GuardChange::SetCounterpartyListing { address: listed, listing } = change else {
+            return err!(ErrorCode::WrongExecuteInstruction);
+        };
+        require_keys_eq!(address, listed, ErrorCode::ListingAccountMismatch);
+
+        let entry = &mut ctx.accounts.listing;
+        entry.guard = guard.key();
+        entry.address = address;
+        entry.listing = listing;
+        entry.updated_ts = now;
+        entry.bump = ctx.bumps.listing;
+
+        msg!("Change #{} executed: {} is {:?}", pending.change_id, address, listing);
+        emit!(ChangeExecuted {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            change,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Apply a queued `RegisterBettingPool` or `ReleaseExposure`
+    /// Permissionless like execute_change; registering creates the pool PDA
+    /// at the caller's expense.
+    pub fn execute_pool_change(ctx: Context<ExecutePoolChange>, pool: Pubkey) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+        let guard = &mut ctx.accounts.guard_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        check_executable(guard, pending, now)?;
+        let change = pending.change;
+        let betting_pool = &mut ctx.accounts.betting_pool;
+        match change {
+            GuardChange::RegisterBettingPool { pool: registered } => {
+                require_keys_eq!(pool, registered, ErrorCode::BettingPoolAccountMismatch);
+                require!(betting_pool.guard == Pubkey::default(), ErrorCode::BettingPoolAlreadyRegistered);
+                betting_pool.guard = guard.key();
+                betting_pool.pool = pool;
+                betting_pool.open_exposure = 0;
+                betting_pool.total_opened = 0;
+                betting_pool.total_payout = 0;
+                betting_pool.bump = ctx.bumps.betting_pool;
+                msg!("Change #{} executed: betting pool {} registered", pending.change_id, pool);
             }
-        }
-        
-        // 2. If not updated, find first empty slot (rule_id == 0)
-        if !updated {
-            for i in 0..guard.policies.len() {
-                if guard.policies[i].rule_id == 0 {
-                    guard.policies[i] = rule;
-                    updated = true;
-                    msg!("Added New Policy Rule ID: {}", rule.rule_id);
-                    break;
+            GuardChange::ReleaseExposure { pool: released, amount, payout } => {
+                require_keys_eq!(pool, released, ErrorCode::BettingPoolAccountMismatch);
+                require!(betting_pool.guard == guard.key(), ErrorCode::BettingPoolNotRegistered);
+                let (pool_exposure, total_exposure) = release_exposure(guard, betting_pool, amount)?;
+                if let Some(payout) = payout {
+                    betting_pool.total_payout = betting_pool.total_payout
+                        .checked_add(payout)
+                        .ok_or(ErrorCode::Overflow)?;
                 }
+
+                msg!("Change #{} executed: {} released on pool {} (payout {:?})", pending.change_id, amount, pool, payout);
+                emit!(ExposureReleased {
+                    guard: pending.guard,
+                    pool,
+                    change_id: pending.change_id,
+                    amount,
+                    payout,
+                    pool_exposure,
+                    total_exposure,
+                    timestamp: now,
+                });
             }
+            _ => return err!(ErrorCode::WrongExecuteInstruction),
         }
-        
-        require!(updated, ErrorCode::PolicyStorageFull);
+
+        emit!(ChangeExecuted {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            change,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Drop a queued change before it is executed
+    /// The proposer may withdraw it at any time; anyone may close it once it
+    /// can no longer pass (rejected by the quorum or the admin set changed).
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let pending = &ctx.accounts.pending_change;
+
+        let withdrawn = ctx.accounts.authority.key() == pending.proposer;
+        let stale = pending.admin_set_version != guard.admin_set_version;
+        let blocking_rejections = guard.admin_count()
+            .saturating_sub(guard.admin_threshold as u32)
+            .saturating_add(1);
+        let rejected = pending.rejections.count_ones() >= blocking_rejections;
+        require!(withdrawn || stale || rejected, ErrorCode::ChangeStillPending);
+
+        msg!("Change #{} cancelled", pending.change_id);
+        emit!(ChangeCancelled {
+            guard: pending.guard,
+            change_id: pending.change_id,
+            authority: ctx.accounts.authority.key(),
+        });
+        Ok(())
+    }
+
+    /// Recompute treasury value from on-chain balances and price feeds
+    /// Permissionless. `remaining_accounts` must hold one
+    /// `[token_account, price_feed]` pair per registered asset, in slot order.
+    pub fn refresh_treasury_value(ctx: Context<RefreshValuation>) -> Result<()> {
+        let guard = &mut ctx.accounts.guard_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        let mut remaining = ctx.remaining_accounts.iter();
+        let mut total_value: u64 = 0;
+        let mut priced_assets = 0;
+
+        for asset in guard.treasury_assets.iter().filter(|a| a.is_registered()) {
+            let token_account = remaining.next().ok_or(ErrorCode::MissingValuationAccount)?;
+            let price_feed = remaining.next().ok_or(ErrorCode::MissingValuationAccount)?;
+            require_keys_eq!(token_account.key(), asset.token_account, ErrorCode::ValuationAccountMismatch);
+            require_keys_eq!(price_feed.key(), asset.price_feed, ErrorCode::ValuationAccountMismatch);
+
+            let balance = read_asset_balance(token_account, asset)?;
+            let price = read_price_feed(price_feed)?;
+            let unit_price = price.conservative_price(&asset.price_tolerance(), now).map_err(ErrorCode::from)?;
+            let value = asset_value(balance, asset.decimals, unit_price, price.expo)?;
+
+            total_value = total_value.checked_add(value).ok_or(ErrorCode::Overflow)?;
+            priced_assets += 1;
+        }
+        require!(priced_assets > 0, ErrorCode::NoTreasuryAssets);
+
+        check_value_drop(guard, total_value, now);
+        guard.treasury_value = total_value;
+        guard.treasury_value_updated_ts = now;
+        msg!("Treasury value refreshed: {} ({} assets)", total_value, priced_assets);
+        emit!(TreasuryValueRefreshed {
+            guard: guard.key(),
+            treasury_value: total_value,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Create the monthly revenue ring buffer for this guard
+    pub fn initialize_revenue_tracker(ctx: Context<InitializeRevenueTracker>) -> Result<()> {
+        let tracker = &mut ctx.accounts.revenue_tracker;
+        tracker.guard = ctx.accounts.guard_state.key();
+        tracker.created_month = month_index(Clock::get()?.unix_timestamp)?;
+        tracker.buckets = [RevenueBucket::default(); REVENUE_MONTHS];
+        tracker.bump = ctx.bumps.revenue_tracker;
+        msg!("Revenue tracker initialized (month {})", tracker.created_month);
+        Ok(())
+    }
+
+    /// Record protocol revenue (whitelisted reporters only)
+    /// `amount` is in native units of `mint`, which must be a registered
+    /// treasury asset. It is stored in quote units (USD, 6 decimals, like
+    /// treasury_value), valued at the bottom of the asset's feed confidence
+    /// interval. Reporters are signer keys or program PDAs signing via CPI:
+    /// grid_fee_sweep reports sweeps as its fee-vault PDA and
+    /// guardian_license_sale reports sales as its sale_state PDA.
+    pub fn record_revenue(ctx: Context<RecordRevenue>, mint: Pubkey, amount: u64) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let reporter = ctx.accounts.reporter.key();
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            reporter != Pubkey::default() && guard.revenue_reporters.contains(&reporter),
+            ErrorCode::UnauthorizedRevenueReporter
+        );
+        require!(amount > 0, ErrorCode::ZeroRevenue);
+
+        let asset = *guard.treasury_assets
+            .iter()
+            .find(|a| a.is_registered() && a.mint == mint)
+            .ok_or(ErrorCode::TreasuryAssetNotFound)?;
+        require_keys_eq!(ctx.accounts.price_feed.key(), asset.price_feed, ErrorCode::ValuationAccountMismatch);
+        let price = read_price_feed(&ctx.accounts.price_feed)?;
+        let value = asset_value(amount, asset.decimals, price.conservative_price(&asset.price_tolerance(), now).map_err(ErrorCode::from)?, price.expo)?;
+        require!(value > 0, ErrorCode::ZeroRevenue);
+
+        let month = month_index(now)?;
+        let tracker = &mut ctx.accounts.revenue_tracker;
+        let bucket = &mut tracker.buckets[month as usize % REVENUE_MONTHS];
+        if bucket.month != month {
+            *bucket = RevenueBucket { month, amount: 0 };
+        }
+        bucket.amount = bucket.amount.checked_add(value).ok_or(ErrorCode::Overflow)?;
+
+        msg!(
+            "Revenue recorded: {} of {} (value {}) by {} (month {} total: {})",
+            amount,
+            mint,
+            value,
+            reporter,
+            month,
+            bucket.amount
+        );
+        emit!(RevenueRecorded {
+            guard: guard.key(),
+            reporter,
+            mint,
+            amount,
+            value,
+            month,
+            month_total: bucket.amount,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Create the daily volume feed used by RULE_04
+    pub fn initialize_volume_feed(ctx: Context<InitializeVolumeFeed>) -> Result<()> {
+        let feed = &mut ctx.accounts.volume_feed;
+        feed.guard = ctx.accounts.guard_state.key();
+        feed.daily_volume = 0;
+        feed.updated_ts = 0;
+        feed.bump = ctx.bumps.volume_feed;
+        msg!("Volume feed initialized for guard {}", feed.guard);
+        Ok(())
+    }
+
+    /// Publish trailing 24h trading volume (volume reporter only)
+    pub fn update_volume_feed(ctx: Context<UpdateVolumeFeed>, daily_volume: u64) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let reporter = ctx.accounts.reporter.key();
+        require!(
+            reporter != Pubkey::default() && reporter == guard.volume_reporter,
+            ErrorCode::UnauthorizedVolumeReporter
+        );
+
+        let feed = &mut ctx.accounts.volume_feed;
+        feed.daily_volume = daily_volume;
+        feed.updated_ts = Clock::get()?.unix_timestamp;
+        msg!("Daily volume updated: {}", daily_volume);
+        emit!(VolumeFeedUpdated {
+            guard: guard.key(),
+            reporter,
+            daily_volume,
+            timestamp: feed.updated_ts,
+        });
+        Ok(())
+    }
+
+    /// Evaluate a strategic buyback (RULE_04)
+    /// Capped at BUYBACK_VOLUME_BPS of daily volume and tracked apart from
+    /// the RULE_02 spend accumulator; cooldown, KYA and drawdown still apply.
+    pub fn evaluate_buyback(
+        ctx: Context<EvaluateBuyback>,
+        amount: u64,
+        agent_kya_level: u8,
+        subject: Pubkey,
+        counterparty: Counterparty,
+    ) -> Result<()> {
+        let guard = &mut ctx.accounts.guard_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        check_call_context(guard, &ctx.accounts.instructions_sysvar)?;
+        check_evaluator(guard, ctx.accounts.authority.as_ref(), &subject)?;
+        let listings = read_listings(
+            &guard.key(),
+            &counterparty,
+            ctx.accounts.destination_listing.as_deref(),
+            ctx.accounts.program_listing.as_deref(),
+        )?;
+        let verdict = build_verdict(
+            guard,
+            rule_feeds(ctx.accounts.revenue_tracker.as_deref(), Some(&ctx.accounts.volume_feed), now)?,
+            listings,
+            amount,
+            agent_kya_level,
+            OperationKind::Buyback,
+            now,
+        )?;
+        if let Some(violation) = verdict.violations.first() {
+            msg!("Buyback blocked: {:?}", verdict.violations);
+            return Err(violation.error().into());
+        }
+
+        let bought_today = record_approval(guard, amount, OperationKind::Buyback, now)?;
+        msg!("Buyback approved. Amount: {}, bought today: {}", amount, bought_today);
+        log_approval(
+            guard,
+            ctx.accounts.decision_log.as_deref_mut(),
+            subject,
+            amount,
+            agent_kya_level,
+            bought_today,
+            now,
+        );
         Ok(())
     }
 
     /// Evaluate a transaction before execution with KYA verification
     /// Returns Ok if allowed, Err if blocked
     /// NOTE: This instruction updates the daily spend accumulator, requiring a mutable context.
+    /// Top-level calls must be signed by `subject`, a registered executor.
     pub fn evaluate_transaction(
         ctx: Context<EvaluateTx>, 
         amount: u64,
         agent_kya_level: u8,
+        subject: Pubkey,
+        counterparty: Counterparty,
     ) -> Result<()> {
         let guard = &mut ctx.accounts.guard_state;
         let clock = Clock::get()?;
+
+        // 0. RULE_01: vCPI prevention
+        check_call_context(guard, &ctx.accounts.instructions_sysvar)?;
+        check_evaluator(guard, ctx.accounts.authority.as_ref(), &subject)?;
+
+        // 1-8. Limits and counterparties (same verdict as simulate_transaction)
+        let listings = read_listings(
+            &guard.key(),
+            &counterparty,
+            ctx.accounts.destination_listing.as_deref(),
+            ctx.accounts.program_listing.as_deref(),
+        )?;
+        let verdict = build_verdict(
+            guard,
+            rule_feeds(ctx.accounts.revenue_tracker.as_deref(), None, clock.unix_timestamp)?,
+            listings,
+            amount,
+            agent_kya_level,
+            OperationKind::Spend,
+            clock.unix_timestamp,
+        )?;
+        if let Some(violation) = verdict.violations.first() {
+            msg!("Transaction blocked: {:?}", verdict.violations);
+            return Err(violation.error().into());
+        }
+
+        // Update state
+        let new_daily_total = record_approval(guard, amount, OperationKind::Spend, clock.unix_timestamp)?;
         
-        // 1. Check cooldown
-        require!(
-            clock.unix_timestamp as u64 > guard.last_operation_ts + guard.cooldown_seconds as u64,
-            ErrorCode::CooldownActive
-        );
-        
-        // 2. Check pause state
-        require!(!guard.paused, ErrorCode::GuardPaused);
-        
-        // 3. KYA Level verification
-        let kya_level = match agent_kya_level {
-            0 => KyaLevel::Anonymous,
-            1 => KyaLevel::Basic,
-            2 => KyaLevel::Verified,
-            3 => KyaLevel::Endorsed,
-            _ => return Err(ErrorCode::InvalidKyaLevel.into()),
-        };
-        
-        // Minimum level check
-        require!(
-            kya_level as u8 >= KyaLevel::Basic as u8,
-            ErrorCode::InsufficientKyaLevel
+        msg!("Transaction approved. KYA Level: {}, Amount: {}", agent_kya_level, amount);
+        msg!("Daily Spend: {} / {}", new_daily_total, verdict.limits.daily_limit);
+        log_approval(
+            guard,
+            ctx.accounts.decision_log.as_deref_mut(),
+            subject,
+            amount,
+            agent_kya_level,
+            new_daily_total,
+            clock.unix_timestamp,
         );
+        Ok(())
+    }
+
+    /// Move treasury funds out of the guard vault, subject to policy
+    /// The vault authority PDA owns every treasury account, so funds cannot
+    /// leave without this evaluation. The transfer is valued at the top of
+    /// the feed's confidence interval and charged to the daily limits, at
+    /// the KYA level of the executor's agent identity.
+    /// `remaining_accounts` carry any Token-2022 transfer hook accounts.
+    pub fn execute_treasury_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteTreasuryTransfer<'info>>,
+        amount: u64,
+        memo: String,
+    ) -> Result<()> {
+        let guard = &mut ctx.accounts.guard_state;
+        let executor = ctx.accounts.executor.key();
+        let destination = ctx.accounts.destination.key();
+        let vault_authority = ctx.accounts.vault_authority.key();
+        let now = Clock::get()?.unix_timestamp;
 
-        // 4. Per-transaction limit based on KYA level
-        let max_amount = (guard.treasury_value as u128)
-            .checked_mul(kya_level.per_tx_limit_bps() as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::Overflow)? as u64;
-            
         require!(
-            amount <= max_amount,
-            ErrorCode::KyaLimitExceeded
+            executor != Pubkey::default() && guard.executors.contains(&executor),
+            ErrorCode::UnauthorizedExecutor
         );
-        
-        // 5. Global Max Drawdown (5%)
-        let global_max = (guard.treasury_value as u128)
-            .checked_mul(guard.max_drawdown_bps as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::Overflow)? as u64;
-
         require!(
-            amount <= global_max,
-            ErrorCode::MaxDrawdownExceeded
+            destination != Pubkey::default() && guard.treasury_destinations.contains(&destination),
+            ErrorCode::DestinationNotAllowed
         );
-        
-        // 6. Daily Spend Limit (0.5%)
-        let current_ts = clock.unix_timestamp;
-        if current_ts - guard.last_reset_ts >= 86400 {
-             guard.daily_spend_accumulator = 0;
-             guard.last_reset_ts = current_ts;
-             msg!("Daily spend limit reset.");
+        require!(memo.len() <= MAX_MEMO_LEN, ErrorCode::MemoTooLong);
+        require!(amount > 0, ErrorCode::ZeroTransferAmount);
+        let agent_kya_level = executor_kya_level(&ctx.accounts.executor_identity, guard, &executor, now)?;
+
+        // Native SOL is held directly by the vault authority
+        let source_key = ctx.accounts.source.as_ref().map_or(vault_authority, |s| s.key());
+        let asset = *guard.treasury_assets
+            .iter()
+            .find(|a| a.is_registered() && a.token_account == source_key)
+            .ok_or(ErrorCode::TreasuryAssetNotFound)?;
+        require_keys_eq!(ctx.accounts.price_feed.key(), asset.price_feed, ErrorCode::ValuationAccountMismatch);
+        let price = read_price_feed(&ctx.accounts.price_feed)?;
+        let value = asset_value(amount, asset.decimals, price.spend_price(&asset.price_tolerance(), now).map_err(ErrorCode::from)?, price.expo)?;
+
+        // Token transfers pay the destination account's owner
+        let (destination_owner, target_program) = if asset.mint == NATIVE_MINT {
+            (destination, system_program::ID)
+        } else {
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(ErrorCode::MissingTransferAccount)?;
+            (read_token_owner(&ctx.accounts.destination, token_program.key)?, token_program.key())
+        };
+        let listings = read_listings(
+            &guard.key(),
+            &Counterparty {
+                destination_owner: Some(destination_owner),
+                target_program: Some(target_program),
+            },
+            Some(&ctx.accounts.destination_listing),
+            Some(&ctx.accounts.program_listing),
+        )?;
+
+        check_call_context(guard, &ctx.accounts.instructions_sysvar)?;
+        let verdict = build_verdict(
+            guard,
+            rule_feeds(ctx.accounts.revenue_tracker.as_deref(), None, now)?,
+            listings,
+            value,
+            agent_kya_level,
+            OperationKind::Spend,
+            now,
+        )?;
+        if let Some(violation) = verdict.violations.first() {
+            msg!("Treasury transfer blocked: {:?}", verdict.violations);
+            return Err(violation.error().into());
         }
+        let new_daily_total = record_approval(guard, value, OperationKind::Spend, now)?;
+        record_exposure(guard, &ctx.accounts.betting_pool, destination_owner, executor, value, now)?;
 
-        let max_daily_spend = (guard.treasury_value as u128)
-            .checked_mul(guard.daily_spend_bps as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::Overflow)? as u64;
+        let guard_key = guard.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"treasury-vault",
+            guard_key.as_ref(),
+            &[ctx.bumps.vault_authority],
+        ]];
 
-        let new_daily_total = guard.daily_spend_accumulator
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+        if asset.mint == NATIVE_MINT {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault_authority.to_account_info(),
+                        to: ctx.accounts.destination.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        } else {
+            let source = ctx.accounts.source.as_ref().ok_or(ErrorCode::MissingTransferAccount)?;
+            let mint = ctx.accounts.mint.as_ref().ok_or(ErrorCode::MissingTransferAccount)?;
+            let token_program = ctx.accounts.token_program.as_ref().ok_or(ErrorCode::MissingTransferAccount)?;
+            require_keys_eq!(mint.key(), asset.mint, ErrorCode::InvalidTreasuryTokenAccount);
+
+            spl_token_2022::onchain::invoke_transfer_checked(
+                token_program.key,
+                source.to_account_info(),
+                mint.to_account_info(),
+                ctx.accounts.destination.to_account_info(),
+                ctx.accounts.vault_authority.to_account_info(),
+                ctx.remaining_accounts,
+                amount,
+                mint.decimals,
+                signer_seeds,
+            )?;
+        }
+
+        if !memo.is_empty() {
+            let memo_program = ctx.accounts.memo_program.as_ref().ok_or(ErrorCode::MissingTransferAccount)?;
+            build_memo(CpiContext::new(memo_program.to_account_info(), BuildMemo {}), memo.as_bytes())?;
+        }
+
+        msg!(
+            "Treasury transfer: {} of {} to {} (value {}). Memo: {}",
+            amount,
+            asset.mint,
+            destination,
+            value,
+            memo
+        );
+        msg!("Daily Spend: {} / {}", new_daily_total, verdict.limits.daily_limit);
+        log_approval(
+            &ctx.accounts.guard_state,
+            ctx.accounts.decision_log.as_deref_mut(),
+            executor,
+            value,
+            agent_kya_level,
+            new_daily_total,
+            now,
+        );
+        emit!(TreasuryTransferExecuted {
+            guard: guard_key,
+            executor,
+            mint: asset.mint,
+            destination,
+            amount,
+            value,
+            memo,
+            timestamp: now,
+        });
+        Ok(())
+    }
 
+    /// Report an evaluation that was denied (monitors only)
+    /// Denied evaluate_transaction calls revert, so they cannot count
+    /// themselves. The verdict is recomputed here so only genuine denials
+    /// move the circuit breaker.
+    pub fn record_denial(
+        ctx: Context<RecordDenial>,
+        amount: u64,
+        agent_kya_level: u8,
+        subject: Pubkey,
+        operation: OperationKind,
+        counterparty: Counterparty,
+    ) -> Result<()> {
+        let guard = &mut ctx.accounts.guard_state;
+        let monitor = ctx.accounts.monitor.key();
         require!(
-            new_daily_total <= max_daily_spend,
-            ErrorCode::DailyLimitExceeded
+            monitor != Pubkey::default() && guard.monitors.contains(&monitor),
+            ErrorCode::UnauthorizedMonitor
         );
+        if guard.paused {
+            msg!("Guard already paused, denial not counted");
+            return Ok(());
+        }
 
-        // Update state
-        guard.daily_spend_accumulator = new_daily_total;
-        guard.last_operation_ts = clock.unix_timestamp as u64;
-        
-        msg!("Transaction approved. KYA Level: {:?}, Amount: {}", kya_level, amount);
-        msg!("Daily Spend: {} / {}", new_daily_total, max_daily_spend);
-
-        // Logic for Dynamic Policies
-        for policy in guard.policies.iter() {
-             if policy.enabled && policy.rule_id == 1 { // Rule ID 1: Custom Max Tx Size
-                 let policy_limit = (guard.treasury_value as u128)
-                     .checked_mul(policy.threshold_bps as u128)
-                     .ok_or(ErrorCode::Overflow)?
-                     .checked_div(10000)
-                     .ok_or(ErrorCode::Overflow)? as u64;
-                     
-                 require!(
-                     amount <= policy_limit,
-                     ErrorCode::PolicyLimitExceeded
-                 );
-             }
+        let now = Clock::get()?.unix_timestamp;
+        let listings = read_listings(
+            &guard.key(),
+            &counterparty,
+            ctx.accounts.destination_listing.as_deref(),
+            ctx.accounts.program_listing.as_deref(),
+        )?;
+        let verdict = build_verdict(
+            guard,
+            rule_feeds(ctx.accounts.revenue_tracker.as_deref(), ctx.accounts.volume_feed.as_deref(), now)?,
+            listings,
+            amount,
+            agent_kya_level,
+            operation,
+            now,
+        )?;
+        require!(!verdict.allowed, ErrorCode::EvaluationNotDenied);
+
+        if let Some(log) = ctx.accounts.decision_log.as_deref_mut() {
+            log.record(DecisionEntry {
+                subject,
+                amount,
+                violation: verdict.violations.first().copied(),
+                timestamp: now,
+            });
         }
-        
+        emit!(DenialRecorded {
+            guard: guard.key(),
+            subject,
+            amount,
+            agent_kya_level,
+            violations: verdict.violations,
+            monitor,
+            timestamp: now,
+        });
+
+        register_denial(guard, now);
+        Ok(())
+    }
+
+    /// Dry-run an evaluation without touching the accumulators
+    /// Returns a Borsh-encoded `PolicyVerdict` via return data, listing every
+    /// failed rule so agents can pre-check a spend and explain refusals.
+    pub fn simulate_transaction(
+        ctx: Context<SimulateTx>,
+        amount: u64,
+        agent_kya_level: u8,
+        operation: OperationKind,
+        counterparty: Counterparty,
+    ) -> Result<()> {
+        let guard = &ctx.accounts.guard_state;
+        let now = Clock::get()?.unix_timestamp;
+        let listings = read_listings(
+            &guard.key(),
+            &counterparty,
+            ctx.accounts.destination_listing.as_deref(),
+            ctx.accounts.program_listing.as_deref(),
+        )?;
+        let verdict = build_verdict(
+            guard,
+            rule_feeds(ctx.accounts.revenue_tracker.as_deref(), ctx.accounts.volume_feed.as_deref(), now)?,
+            listings,
+            amount,
+            agent_kya_level,
+            operation,
+            now,
+        )?;
+
+        msg!(
+            "Simulation: {} (violations: {:?})",
+            if verdict.allowed { "ALLOWED" } else { "DENIED" },
+            verdict.violations
+        );
+        set_return_data(&verdict.try_to_vec()?);
         Ok(())
     }
     
+    /// Same evaluation as evaluate_transaction, for callers that hold the
+    /// guard through the EvaluateTxMut context
     pub fn evaluate_transaction_mut(
         ctx: Context<EvaluateTxMut>, 
         amount: u64,
         agent_kya_level: u8,
+        subject: Pubkey,
+        counterparty: Counterparty,
     ) -> Result<()> {
          let guard = &mut ctx.accounts.guard_state;
          let clock = Clock::get()?;
-         
-         if clock.unix_timestamp as u64 <= guard.last_operation_ts + guard.cooldown_seconds as u64 {
-             return Err(ErrorCode::CooldownActive.into());
-         }
-         
-         if guard.paused {
-             return Err(ErrorCode::GuardPaused.into());
-         }
-         
-         let kya_level = match agent_kya_level {
-            0 => KyaLevel::Anonymous,
-            1 => KyaLevel::Basic,
-            2 => KyaLevel::Verified,
-            3 => KyaLevel::Endorsed,
-            _ => return Err(ErrorCode::InvalidKyaLevel.into()),
-        };
-        
-        let max_amount = (guard.treasury_value as u128)
-            .checked_mul(kya_level.per_tx_limit_bps() as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::Overflow)? as u64;
-            
-         if amount > max_amount {
-             return Err(ErrorCode::KyaLimitExceeded.into());
-         }
-
-        // Daily Spend Logic
-        let current_ts = clock.unix_timestamp;
-         if current_ts - guard.last_reset_ts >= 86400 {
-              guard.daily_spend_accumulator = 0;
-              guard.last_reset_ts = current_ts;
-         }
-
-        let max_daily_spend = (guard.treasury_value as u128)
-            .checked_mul(guard.daily_spend_bps as u128)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(10000)
-            .ok_or(ErrorCode::Overflow)? as u64;
 
-        let new_daily_total = guard.daily_spend_accumulator
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
-
-        if new_daily_total > max_daily_spend {
-             return Err(ErrorCode::DailyLimitExceeded.into());
-        }
+         check_call_context(guard, &ctx.accounts.instructions_sysvar)?;
+         check_evaluator(guard, ctx.accounts.authority.as_ref(), &subject)?;
 
-        guard.daily_spend_accumulator = new_daily_total;
-        guard.last_operation_ts = clock.unix_timestamp as u64;
+         let listings = read_listings(
+             &guard.key(),
+             &counterparty,
+             ctx.accounts.destination_listing.as_deref(),
+             ctx.accounts.program_listing.as_deref(),
+         )?;
+         let verdict = build_verdict(
+             guard,
+             rule_feeds(ctx.accounts.revenue_tracker.as_deref(), None, clock.unix_timestamp)?,
+             listings,
+             amount,
+             agent_kya_level,
+             OperationKind::Spend,
+             clock.unix_timestamp,
+         )?;
+         if let Some(violation) = verdict.violations.first() {
+             return Err(violation.error().into());
+         }
 
-        Ok(())
+         let new_daily_total = record_approval(guard, amount, OperationKind::Spend, clock.unix_timestamp)?;
+         log_approval(
+             guard,
+             ctx.accounts.decision_log.as_deref_mut(),
+             subject,
+             amount,
+             agent_kya_level,
+             new_daily_total,
+             clock.unix_timestamp,
+         );
+         Ok(())
     }
 
 
-    /// Emergency pause (DAO only)
-    pub fn pause(ctx: Context<AdminOnly>) -> Result<()> {
-        ctx.accounts.guard_state.paused = true;
-        msg!("Policy Guard PAUSED");
+    /// Create the on-chain ring buffer of recent guard decisions
+    pub fn initialize_decision_log(ctx: Context<InitializeDecisionLog>) -> Result<()> {
+        let log = &mut ctx.accounts.decision_log;
+        log.guard = ctx.accounts.guard_state.key();
+        log.total = 0;
+        log.entries = [DecisionEntry::default(); DECISION_LOG_LEN];
+        log.bump = ctx.bumps.decision_log;
+        msg!("Decision log initialized for guard {}", log.guard);
         Ok(())
     }
 
-    /// Resume operations (DAO only)
-    pub fn unpause(ctx: Context<AdminOnly>) -> Result<()> {
-        ctx.accounts.guard_state.paused = false;
-        msg!("Policy Guard RESUMED");
+    /// Emergency pause (any admin or guardian)
+    /// Unpausing needs an admin quorum via a queued `GuardChange::Unpause`.
+    pub fn pause(ctx: Context<PauseGuard>) -> Result<()> {
+        let guard = &mut ctx.accounts.guard_state;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            guard.admin_index(&authority).is_some() || guard.is_guardian(&authority),
+            ErrorCode::UnauthorizedPauser
+        );
+
+        trip(guard, TripCause::Manual, Clock::get()?.unix_timestamp);
+        msg!("Policy Guard PAUSED by {}", authority);
         Ok(())
     }
 }
 
 // ============================================================================
-// STATE
+// CONSTITUTIONAL BOUNDS
+// Compiled in on purpose: no governance vote can move a parameter outside
+// the ranges set by the Constitution.
 // ============================================================================
 
-#[account]
-pub struct GuardState {
-    /// DAO multisig that can modify rules
-    pub admin: Pubkey,
-    /// Emergency stop flag
-    pub paused: bool,
-    /// Max daily spend in basis points (0.5% = 50 bps)
-    pub daily_spend_bps: u16,
-    /// Max single transaction drawdown in bps (5% = 500 bps)
-    pub max_drawdown_bps: u16,
-    /// Cooldown between operations in seconds
-    pub cooldown_seconds: u32,
-    /// Timestamp of last treasury operation
-    pub last_operation_ts: u64,
-    /// Agent Identity program ID for KYA verification
-    pub agent_identity_program: Pubkey,
-    /// Treasury value for limit calculations
-    pub treasury_value: u64,
-    /// Daily spend accumulator (resets every 24h)
-    pub daily_spend_accumulator: u64,
-    /// Timestamp of the last daily reset
-    pub last_reset_ts: i64,
-    /// Active Policies (Replacing reserved space with 8 fixed slots)
-    pub policies: [PolicyRule; 8],
-}
+/// Law 2: daily ops cap 0.5% (adjustable 0.1% - 2.0%)
+pub const DEFAULT_DAILY_SPEND_BPS: u16 = 50;
+pub const MIN_DAILY_SPEND_BPS: u16 = 10;
+pub const MAX_DAILY_SPEND_BPS: u16 = 200;
 
-/// KYA Level requirements for operations
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
-pub enum KyaLevel {
-    Anonymous = 0,
-    Basic = 1,
-    Verified = 2,
-    Endorsed = 3,
-}
+/// Law 2: single transaction cap 5% (adjustable 1% - 10%)
+pub const DEFAULT_MAX_DRAWDOWN_BPS: u16 = 500;
+pub const MIN_MAX_DRAWDOWN_BPS: u16 = 100;
+pub const MAX_MAX_DRAWDOWN_BPS: u16 = 1000;
 
-/// Spending limits by KYA level (in basis points of treasury)
-impl KyaLevel {
-    pub fn daily_limit_bps(&self) -> u64 {
-        match self {
-            KyaLevel::Anonymous => 0,
-            KyaLevel::Basic => 10,     // 0.1%
-            KyaLevel::Verified => 100, // 1%
-            KyaLevel::Endorsed => 500, // 5%
+/// RULE_03: single transaction cap as bps of prior-month revenue (1% - 10%)
+pub const MIN_REVENUE_CAP_BPS: u16 = 100;
+pub const MAX_REVENUE_CAP_BPS: u16 = 1000;
+
+/// RULE_05: 4-hour cooldown (adjustable 1h - 24h)
+/// Never zero: RULE_01 relies on it to reject repeated CPI evaluations.
+pub const DEFAULT_COOLDOWN_SECONDS: u32 = 4 * 60 * 60;
+pub const MIN_COOLDOWN_SECONDS: u32 = 60 * 60;
+pub const MAX_COOLDOWN_SECONDS: u32 = 24 * 60 * 60;
+
+/// Law 2: betting exposure per pool 5% of treasury (adjustable 1% - 10%)
+pub const DEFAULT_POOL_EXPOSURE_BPS: u16 = 500;
+pub const MIN_POOL_EXPOSURE_BPS: u16 = 100;
+pub const MAX_POOL_EXPOSURE_BPS: u16 = 1000;
+
+/// Aggregate betting exposure across pools: 15% of treasury (fixed)
+pub const TOTAL_EXPOSURE_BPS: u16 = 1500;
+
+/// Law 1: 72-hour timelock (non-reducible below 24h, max 168h)
+pub const DEFAULT_TIMELOCK_SECONDS: u32 = 72 * 60 * 60;
+pub const MIN_TIMELOCK_SECONDS: u32 = 24 * 60 * 60;
+pub const MAX_TIMELOCK_SECONDS: u32 = 168 * 60 * 60;
+
+// ============================================================================
+// ADMINS AND GUARDIANS
+// Admins act as an M-of-N multisig through PendingChange proposals.
+// Guardians can only pause, so a leaked guardian key cannot loosen policy.
+// ============================================================================
+
+/// Admin slots (bit positions in PendingChange approvals)
+pub const MAX_ADMINS: usize = 8;
+/// Pause-only guardian slots
+pub const MAX_GUARDIANS: usize = 8;
+
+/// Reject admin sets with duplicates or an unreachable threshold
+fn validate_admin_set(admins: &[Pubkey; MAX_ADMINS], threshold: u8) -> Result<()> {
+    let mut members = 0;
+    for (i, admin) in admins.iter().enumerate() {
+        if *admin == Pubkey::default() {
+            continue;
         }
+        require!(!admins[..i].contains(admin), ErrorCode::DuplicateAdmin);
+        members += 1;
     }
-    
-    pub fn per_tx_limit_bps(&self) -> u64 {
-        match self {
-            KyaLevel::Anonymous => 0,
-            KyaLevel::Basic => 1,      // 0.01%
-            KyaLevel::Verified => 10,  // 0.1%
-            KyaLevel::Endorsed => 100, // 1%
+    require!(threshold >= 1 && threshold <= members, ErrorCode::InvalidAdminThreshold);
+    Ok(())
+}
+
+impl GuardState {
+    /// Slot of `key` in the admin set
+    pub fn admin_index(&self, key: &Pubkey) -> Option<usize> {
+        if *key == Pubkey::default() {
+            return None;
         }
+        self.admins.iter().position(|a| a == key)
     }
-}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct GuardConfig {
-    pub admin: Pubkey,
-}
+    pub fn admin_count(&self) -> u32 {
+        self.admins.iter().filter(|a| **a != Pubkey::default()).count() as u32
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
-pub struct PolicyRule {
-    pub rule_id: u8,
-    pub threshold_bps: u16,
-    pub enabled: bool,
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.guardians.contains(key)
+    }
 }
 
 // ============================================================================
-// CONTEXTS
+// TIMELOCKED CHANGES
 // ============================================================================
 
-#[derive(Accounts)]
-pub struct InitializeGuard<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + std::mem::size_of::<GuardState>(),
-        seeds = [b"guard"],
-        bump
-    )]
-    pub guard_state: Account<'info, GuardState>,
-    
-    pub system_program: Program<'info, System>,
+/// Window after the ETA in which a queued change may still be executed
+pub const CHANGE_GRACE_PERIOD_SECONDS: i64 = 14 * 24 * 60 * 60;
+
+/// Execution time of a change queued at `now`: the requested `eta` for
+/// timelocked changes (no earlier than the timelock allows), `now` otherwise
+fn queue_eta(guard: &GuardState, change: &GuardChange, eta: i64, now: i64) -> Result<i64> {
+    if !change.requires_timelock() {
+        return Ok(now);
+    }
+    let earliest = now
+        .checked_add(guard.timelock_seconds as i64)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(eta >= earliest, ErrorCode::TimelockTooShort);
+    Ok(eta)
 }
 
-#[derive(Accounts)]
-pub struct ManagePolicy<'info> {
-    #[account(
-        mut,
-        seeds = [b"guard"],
-        bump,
-        has_one = admin
-    )]
-    pub guard_state: Account<'info, GuardState>,
-    
-    pub admin: Signer<'info>,
+/// Quorum, admin-set and timelock checks shared by the execute instructions
+fn check_executable(guard: &GuardState, pending: &PendingChange, now: i64) -> Result<()> {
+    require!(pending.admin_set_version == guard.admin_set_version, ErrorCode::StaleAdminSet);
+    require!(
+        pending.approvals.count_ones() >= guard.admin_threshold as u32,
+        ErrorCode::InsufficientApprovals
+    );
+
+    // The current timelock applies even to changes queued under a shorter one
+    let unlock_ts = if pending.change.requires_timelock() {
+        pending.queued_at
+            .checked_add(guard.timelock_seconds as i64)
+            .ok_or(ErrorCode::Overflow)?
+            .max(pending.eta)
+    } else {
+        pending.queued_at
+    };
+    require!(now >= unlock_ts, ErrorCode::TimelockNotElapsed);
+
+    let expiry_ts = unlock_ts
+        .checked_add(CHANGE_GRACE_PERIOD_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(now <= expiry_ts, ErrorCode::ChangeExpired);
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct UpdateStats<'info> {
+/// RULE_05 cooldown bounds; the floor also rejects repeated CPI evaluations
+fn validate_cooldown(seconds: u32) -> Result<()> {
+    require!(
+        (MIN_COOLDOWN_SECONDS..=MAX_COOLDOWN_SECONDS).contains(&seconds),
+        ErrorCode::CooldownOutOfBounds
+    );
+    Ok(())
+}
+
+/// Reject changes that could never be applied, before they sit in the queue
+fn validate_change(change: &GuardChange) -> Result<()> {
+    match change {
+        GuardChange::AddPolicy(rule) => {
+            require!(rule.rule_id != 0, ErrorCode::InvalidPolicyRule);
+            if rule.rule_id == RULE_REVENUE_CAP {
+                require!(
+                    (MIN_REVENUE_CAP_BPS..=MAX_REVENUE_CAP_BPS).contains(&rule.threshold_bps),
+                    ErrorCode::RevenueCapOutOfBounds
+                );
+            }
+        }
+        GuardChange::SetRevenueReporter { reporter, .. } => {
+            require!(*reporter != Pubkey::default(), ErrorCode::UnauthorizedRevenueReporter);
+        }
+        GuardChange::SetAllowedCaller { program, .. } => {
+            require!(*program != Pubkey::default(), ErrorCode::CallerNotAllowed);
+        }
+        GuardChange::SetMonitor { monitor, .. } => {
+            require!(*monitor != Pubkey::default(), ErrorCode::UnauthorizedMonitor);
+        }
+        GuardChange::SetCircuitBreaker(config) => validate_breaker_config(config)?,
+        GuardChange::SetAdmins { admins, threshold } => validate_admin_set(admins, *threshold)?,
+        GuardChange::SetGuardian { guardian, .. } => {
+            require!(*guardian != Pubkey::default(), ErrorCode::UnauthorizedPauser);
+        }
+        GuardChange::Unpause => {}
+        GuardChange::SetExecutor { executor, .. } => {
+            require!(*executor != Pubkey::default(), ErrorCode::UnauthorizedExecutor);
+        }
+        GuardChange::SetTreasuryDestination { destination, .. } => {
+            require!(*destination != Pubkey::default(), ErrorCode::DestinationNotAllowed);
+        }
+        GuardChange::SetVolumeReporter { .. } => {}
+        GuardChange::SetCounterpartyListing { address, .. } => {
+            require!(*address != Pubkey::default(), ErrorCode::ListingAccountMismatch);
+        }
+        GuardChange::SetCounterpartyRule { kya_level, .. } => {
+            require!(KyaLevel::from_u8(*kya_level).is_some(), ErrorCode::InvalidKyaLevel);
+        }
+        GuardChange::SetTreasuryAsset(asset) => {
+            require!(asset.is_registered(), ErrorCode::InvalidTreasuryAsset);
+            require!(
+                asset.mint != NATIVE_MINT || asset.decimals == NATIVE_DECIMALS,
+                ErrorCode::InvalidTreasuryAsset
+            );
+            require!(
+                asset.max_confidence_bps > 0 && asset.max_confidence_bps <= MAX_PRICE_CONFIDENCE_BPS,
+                ErrorCode::InvalidTreasuryAsset
+            );
+            require!(
+                asset.max_staleness_seconds > 0 && asset.max_staleness_seconds <= MAX_PRICE_STALENESS_SECONDS,
+                ErrorCode::InvalidTreasuryAsset
+            );
+        }
+        GuardChange::RemoveTreasuryAsset { .. } => {}
+        GuardChange::SetDailySpendBps { bps } => {
+            require!(
+                (MIN_DAILY_SPEND_BPS..=MAX_DAILY_SPEND_BPS).contains(bps),
+                ErrorCode::DailySpendOutOfBounds
+            );
+        }
+        GuardChange::SetMaxDrawdownBps { bps } => {
+            require!(
+                (MIN_MAX_DRAWDOWN_BPS..=MAX_MAX_DRAWDOWN_BPS).contains(bps),
+                ErrorCode::MaxDrawdownOutOfBounds
+            );
+        }
+        GuardChange::SetCooldownSeconds { seconds } => validate_cooldown(*seconds)?,
+        GuardChange::SetTimelock { seconds } => {
+            require!(
+                (MIN_TIMELOCK_SECONDS..=MAX_TIMELOCK_SECONDS).contains(seconds),
+                ErrorCode::TimelockOutOfBounds
+            );
+        }
+        GuardChange::SetPoolExposureBps { bps } => {
+            require!(
+                (MIN_POOL_EXPOSURE_BPS..=MAX_POOL_EXPOSURE_BPS).contains(bps),
+                ErrorCode::ExposureCapOutOfBounds
+            );
+        }
+        GuardChange::RegisterBettingPool { pool } => {
+            require!(*pool != Pubkey::default(), ErrorCode::BettingPoolAccountMismatch);
+        }
+        GuardChange::ReleaseExposure { amount, .. } => {
+            require!(*amount > 0, ErrorCode::ZeroExposureAmount);
+        }
+    }
+    Ok(())
+}
+
+fn apply_change(guard: &mut GuardState, change: GuardChange, now: i64) -> Result<()> {
+    validate_change(&change)?;
+
+    match change {
+        GuardChange::AddPolicy(rule) => {
+            // 1. Try to update existing rule with same ID
+            if let Some(slot) = guard.policies.iter_mut().find(|p| p.rule_id == rule.rule_id) {
+                *slot = rule;
+                msg!("Updated Policy Rule ID: {}", rule.rule_id);
+                return Ok(());
+            }
+
+            // 2. Otherwise take the first empty slot (rule_id == 0)
+            let slot = guard.policies
+                .iter_mut()
+                .find(|p| p.rule_id == 0)
+                .ok_or(ErrorCode::PolicyStorageFull)?;
+            *slot = rule;
+            msg!("Added New Policy Rule ID: {}", rule.rule_id);
+        }
+        GuardChange::SetTreasuryAsset(asset) => {
+            let slot = match guard.treasury_assets.iter().position(|a| a.token_account == asset.token_account) {
+                Some(i) => i,
+                None => guard.treasury_assets
+                    .iter()
+                    .position(|a| !a.is_registered())
+                    .ok_or(ErrorCode::TreasuryAssetStorageFull)?,
+            };
+            guard.treasury_assets[slot] = asset;
+            // Composition changed: force a refresh before the next evaluation
+            guard.treasury_value_updated_ts = 0;
+            msg!("Treasury asset registered: {} (feed {})", asset.token_account, asset.price_feed);
+        }
+        GuardChange::RemoveTreasuryAsset { token_account } => {
+            let slot = guard.treasury_assets
+                .iter_mut()
+                .find(|a| a.is_registered() && a.token_account == token_account)
+                .ok_or(ErrorCode::TreasuryAssetNotFound)?;
+            *slot = TreasuryAsset::default();
+            guard.treasury_value_updated_ts = 0;
+            msg!("Treasury asset removed: {}", token_account);
+        }
+        GuardChange::SetRevenueReporter { reporter, allowed } => {
+            update_key_set(&mut guard.revenue_reporters, reporter, allowed, ErrorCode::RevenueReporterStorageFull)?;
+            msg!("Revenue reporter {}: {}", reporter, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::SetAllowedCaller { program, allowed } => {
+            update_key_set(&mut guard.allowed_callers, program, allowed, ErrorCode::AllowedCallerStorageFull)?;
+            msg!("Caller program {}: {}", program, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::SetMonitor { monitor, allowed } => {
+            update_key_set(&mut guard.monitors, monitor, allowed, ErrorCode::MonitorStorageFull)?;
+            msg!("Monitor {}: {}", monitor, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::SetCircuitBreaker(config) => {
+            guard.breaker_config = config;
+            msg!("Circuit breaker updated: {:?}", config);
+        }
+        GuardChange::SetAdmins { admins, threshold } => {
+            guard.admins = admins;
+            guard.admin_threshold = threshold;
+            // Invalidates approvals collected under the old set
+            guard.admin_set_version = guard.admin_set_version
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+            msg!("Admin set updated: {}-of-{}", threshold, guard.admin_count());
+        }
+        GuardChange::SetGuardian { guardian, allowed } => {
+            update_key_set(&mut guard.guardians, guardian, allowed, ErrorCode::GuardianStorageFull)?;
+            msg!("Guardian {}: {}", guardian, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::Unpause => {
+            require!(guard.paused, ErrorCode::GuardNotPaused);
+            let unlock_ts = guard.breaker.tripped_at
+                .checked_add(guard.breaker_config.unpause_cooldown_seconds as i64)
+                .ok_or(ErrorCode::Overflow)?;
+            require!(now >= unlock_ts, ErrorCode::UnpauseCooldownActive);
+
+            msg!("Policy Guard RESUMED (was: {:?})", guard.breaker.trip_cause);
+            guard.paused = false;
+            guard.breaker = CircuitBreakerState::default();
+        }
+        GuardChange::SetExecutor { executor, allowed } => {
+            update_key_set(&mut guard.executors, executor, allowed, ErrorCode::ExecutorStorageFull)?;
+            msg!("Executor {}: {}", executor, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::SetTreasuryDestination { destination, allowed } => {
+            update_key_set(&mut guard.treasury_destinations, destination, allowed, ErrorCode::DestinationStorageFull)?;
+            msg!("Treasury destination {}: {}", destination, if allowed { "allowed" } else { "revoked" });
+        }
+        GuardChange::SetVolumeReporter { reporter } => {
+            guard.volume_reporter = reporter;
+            msg!("Volume reporter: {}", reporter);
+        }
+        GuardChange::SetCounterpartyListing { .. }
+        | GuardChange::RegisterBettingPool { .. }
+        | GuardChange::ReleaseExposure { .. } => {
+            return err!(ErrorCode::WrongExecuteInstruction);
+        }
+        GuardChange::SetCounterpartyRule { kya_level, rule } => {
+            guard.counterparty_rules[kya_level as usize] = rule;
+            msg!("Counterparty rule for KYA level {}: {:?}", kya_level, rule);
+        }
+        GuardChange::SetDailySpendBps { bps } => {
+            guard.daily_spend_bps = bps;
+            msg!("Daily spend limit updated: {} bps", bps);
+        }
+        GuardChange::SetMaxDrawdownBps { bps } => {
+            guard.max_drawdown_bps = bps;
+            msg!("Max drawdown updated: {} bps", bps);
+        }
+        GuardChange::SetCooldownSeconds { seconds } => {
+            // Checked again here: RULE_01 relies on a non-zero cooldown
+            validate_cooldown(seconds)?;
+            guard.cooldown_seconds = seconds;
+            msg!("Cooldown updated: {}s", seconds);
+        }
+        GuardChange::SetTimelock { seconds } => {
+            guard.timelock_seconds = seconds;
+            msg!("Timelock updated: {}s", seconds);
+        }
+        GuardChange::SetPoolExposureBps { bps } => {
+            // Lowering the cap does not force-close open exposure, it only
+            // blocks new bets until enough is released
+            guard.pool_exposure_bps = bps;
+            msg!("Per-pool exposure cap updated: {} bps", bps);
+        }
+    }
+    Ok(())
+}
+
+/// Add or remove `key` in a fixed-slot allowlist (default = empty slot)
+fn update_key_set(slots: &mut [Pubkey], key: Pubkey, allowed: bool, full: ErrorCode) -> Result<()> {
+    let existing = slots.iter().position(|k| *k == key);
+    match (allowed, existing) {
+        (true, None) => {
+            let slot = slots
+                .iter_mut()
+                .find(|k| **k == Pubkey::default())
+                .ok_or(full)?;
+            *slot = key;
+        }
+        (false, Some(i)) => slots[i] = Pubkey::default(),
+        _ => {}
+    }
+    Ok(())
+}
+
+// ============================================================================
+// TREASURY VALUATION
+// ============================================================================
+
+/// Fixed number of treasury token accounts that can be priced
+pub const MAX_TREASURY_ASSETS: usize = 4;
+/// Evaluations fail closed if the valuation is older than this
+pub const MAX_VALUATION_AGE_SECONDS: i64 = 60 * 60;
+/// Upper bounds for per-asset feed tolerances
+pub const MAX_PRICE_STALENESS_SECONDS: u32 = 60 * 60;
+pub const MAX_PRICE_CONFIDENCE_BPS: u16 = 500;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Parse the feed account registered for an asset (layout and checks in
+/// grid_policy_core, shared with the transfer hook)
+fn read_price_feed(feed: &AccountInfo) -> Result<PriceFeedData> {
+    Ok(PriceFeedData::parse(&feed.try_borrow_data()?).map_err(ErrorCode::from)?)
+}
+
+/// Read the balance of a treasury asset: lamports for native SOL held by the
+/// vault authority, otherwise an SPL Token / Token-2022 account holding `mint`
+fn read_asset_balance(account: &AccountInfo, asset: &TreasuryAsset) -> Result<u64> {
+    if asset.mint == NATIVE_MINT {
+        require_keys_eq!(*account.owner, system_program::ID, ErrorCode::InvalidTreasuryTokenAccount);
+        return Ok(account.lamports());
+    }
+    read_token_balance(account, &asset.mint)
+}
+
+/// Read the balance of an SPL Token / Token-2022 account holding `mint`
+fn read_token_balance(token_account: &AccountInfo, mint: &Pubkey) -> Result<u64> {
+    require!(
+        token_account.owner == &TOKEN_PROGRAM_ID || token_account.owner == &TOKEN_2022_PROGRAM_ID,
+        ErrorCode::InvalidTreasuryTokenAccount
+    );
+    let data = token_account.try_borrow_data()?;
+    // Base layout shared by both programs: mint (0..32), owner (32..64), amount (64..72)
+    require!(data.len() >= 165, ErrorCode::InvalidTreasuryTokenAccount);
+    require!(&data[0..32] == mint.as_ref(), ErrorCode::InvalidTreasuryTokenAccount);
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+/// Value `balance` base units at `price * 10^expo` USD per whole token,
+/// returned in quote units
+fn asset_value(balance: u64, decimals: u8, price: u64, expo: i32) -> Result<u64> {
+    Ok(core::usd_value(balance, decimals, price, expo, QUOTE_DECIMALS).map_err(ErrorCode::from)?)
+}
+
+// ============================================================================
+// TREASURY VAULT
+// Treasury accounts are owned by the PDA [b"treasury-vault", guard], so
+// spending that bypasses the guard is impossible by construction.
+// ============================================================================
+
+/// Allowlisted transfer destinations (token accounts or system accounts)
+pub const MAX_TREASURY_DESTINATIONS: usize = 8;
+/// Keys allowed to request treasury transfers (AI CEO, keepers)
+pub const MAX_EXECUTORS: usize = 4;
+/// Longest memo attached to a treasury transfer
+pub const MAX_MEMO_LEN: usize = 128;
+/// Registering this mint with `token_account` = vault authority values the
+/// vault's native SOL balance
+pub const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+pub const NATIVE_DECIMALS: u8 = 9;
+
+/// KYA level a treasury transfer is evaluated at, from the executor's identity
+/// Same standing as the transfer hook: no identity or a suspension is
+/// level 0, an expired subscription falls back to level 1.
+fn executor_kya_level(account: &AccountInfo, guard: &GuardState, executor: &Pubkey, now: i64) -> Result<u8> {
+    if account.owner != &guard.agent_identity_program || account.data_is_empty() {
+        msg!("Executor {} has no agent identity", executor);
+        return Ok(0);
+    }
+    let identity = AgentIdentity::load(&account.try_borrow_data()?)?;
+    if identity.agent_wallet != *executor || identity.suspended {
+        return Ok(0);
+    }
+    if identity.subscription_expiry > now {
+        Ok(identity.kya_level)
+    } else {
+        Ok(identity.kya_level.min(1))
+    }
+}
+
+// ============================================================================
+// BETTING EXPOSURE (Law 2)
+// Treasury transfers to a registered pool stay open exposure until the bet
+// is settled or cancelled. Caps are measured against the current valuation.
+// Registering a pool and releasing its exposure are guard changes, applied
+// by execute_pool_change.
+// ============================================================================
+
+/// Open exposure of the treasury to one betting pool
+#[account]
+#[derive(InitSpace)]
+pub struct BettingPool {
+    /// Guard this pool is tracked under
+    pub guard: Pubkey,
+    /// Pool identifier (the pool program's account or market key)
+    pub pool: Pubkey,
+    /// Exposure not yet settled or cancelled, in quote units
+    pub open_exposure: u64,
+    /// Exposure ever opened on this pool
+    pub total_opened: u64,
+    /// Payouts reported on settlement
+    pub total_payout: u64,
+    pub bump: u8,
+}
+
+/// Charge a treasury transfer to the destination owner's betting pool, if
+/// one is registered. The value is what actually left the vault, so the
+/// per-pool and aggregate caps cannot be sidestepped by under-reporting.
+fn record_exposure(
+    guard: &mut Account<GuardState>,
+    account: &AccountInfo,
+    destination_owner: Pubkey,
+    executor: Pubkey,
+    value: u64,
+    now: i64,
+) -> Result<()> {
+    let guard_key = guard.key();
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"pool", guard_key.as_ref(), destination_owner.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(account.key(), expected, ErrorCode::BettingPoolAccountMismatch);
+    if account.owner != &crate::ID || account.data_is_empty() {
+        return Ok(());
+    }
+    let mut betting_pool = BettingPool::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+
+    let pool_exposure = betting_pool.open_exposure
+        .checked_add(value)
+        .ok_or(ErrorCode::Overflow)?;
+    let total_exposure = guard.open_exposure
+        .checked_add(value)
+        .ok_or(ErrorCode::Overflow)?;
+    let pool_cap = core::bps_of(guard.treasury_value, guard.pool_exposure_bps as u64)
+        .map_err(ErrorCode::from)?;
+    let total_cap = core::bps_of(guard.treasury_value, TOTAL_EXPOSURE_BPS as u64)
+        .map_err(ErrorCode::from)?;
+    require!(pool_exposure <= pool_cap, ErrorCode::PoolExposureExceeded);
+    require!(total_exposure <= total_cap, ErrorCode::TotalExposureExceeded);
+
+    betting_pool.open_exposure = pool_exposure;
+    betting_pool.total_opened = betting_pool.total_opened
+        .checked_add(value)
+        .ok_or(ErrorCode::Overflow)?;
+    betting_pool.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    guard.open_exposure = total_exposure;
+
+    msg!(
+        "Exposure opened on pool {}: {} (pool {} / {}, total {} / {})",
+        destination_owner,
+        value,
+        pool_exposure,
+        pool_cap,
+        total_exposure,
+        total_cap
+    );
+    emit!(ExposureOpened {
+        guard: guard_key,
+        pool: destination_owner,
+        executor,
+        amount: value,
+        pool_exposure,
+        total_exposure,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Release exposure on a settled or cancelled bet; returns the pool and
+/// aggregate exposure left. Only a quorum-approved ReleaseExposure gets
+/// here, since every release frees room for new bets.
+fn release_exposure(guard: &mut GuardState, betting_pool: &mut BettingPool, amount: u64) -> Result<(u64, u64)> {
+    require!(amount > 0, ErrorCode::ZeroExposureAmount);
+
+    betting_pool.open_exposure = betting_pool.open_exposure
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ExposureUnderflow)?;
+    guard.open_exposure = guard.open_exposure
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ExposureUnderflow)?;
+    Ok((betting_pool.open_exposure, guard.open_exposure))
+}
+
+// ============================================================================
+// REVENUE ACCOUNTING (RULE_03)
+// ============================================================================
+
+/// Reporter slots (fee sweep, license sale, spares)
+pub const MAX_REVENUE_REPORTERS: usize = 4;
+
+fn month_index(ts: i64) -> Result<u32> {
+    core::month_index(ts).map_err(|e| ErrorCode::from(e).into())
+}
+
+/// Monthly revenue ring buffer
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueTracker {
+    /// Guard this tracker feeds
+    pub guard: Pubkey,
+    /// Month the tracker was created; earlier months are not averaged
+    pub created_month: u32,
+    /// Buckets indexed by `month % REVENUE_MONTHS`
+    pub buckets: [RevenueBucket; REVENUE_MONTHS],
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct RevenueBucket {
+    pub month: u32,
+    /// Revenue recorded in `month`, in quote units
+    pub amount: u64,
+}
+
+impl RevenueTracker {
+    /// Average revenue over the last completed months (see grid_policy_core)
+    pub fn prior_month_average(&self, now: i64) -> Result<u64> {
+        let buckets = self.buckets.map(|b| (b.month, b.amount));
+        core::prior_month_average(&buckets, self.created_month, now)
+            .map_err(|e| ErrorCode::from(e).into())
+    }
+}
+
+// ============================================================================
+// STRATEGIC BUYBACKS (RULE_04)
+// ============================================================================
+
+/// Buybacks fail closed if the volume feed is older than this
+pub const MAX_VOLUME_AGE_SECONDS: i64 = 60 * 60;
+
+/// Trailing 24h trading volume, pushed by the guard's volume reporter
+#[account]
+#[derive(InitSpace)]
+pub struct VolumeFeed {
+    /// Guard this feed belongs to
+    pub guard: Pubkey,
+    /// Trailing 24h volume in quote units (USD, 6 decimals)
+    pub daily_volume: u64,
+    /// Timestamp of the last update (0 = never reported)
+    pub updated_ts: i64,
+    pub bump: u8,
+}
+
+impl VolumeFeed {
+    /// Reported volume, or None if it was never reported or is stale
+    pub fn fresh_volume(&self, now: i64) -> Option<u64> {
+        let fresh = self.updated_ts > 0
+            && now.saturating_sub(self.updated_ts) <= MAX_VOLUME_AGE_SECONDS;
+        fresh.then_some(self.daily_volume)
+    }
+}
+
+/// What an evaluated amount is for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    /// Ordinary spend, charged to the daily limit (RULE_02)
+    Spend,
+    /// Strategic buyback, charged to the volume cap (RULE_04)
+    Buyback,
+}
+
+impl From<OperationKind> for core::Operation {
+    fn from(kind: OperationKind) -> Self {
+        match kind {
+            OperationKind::Spend => core::Operation::Spend,
+            OperationKind::Buyback => core::Operation::Buyback,
+        }
+    }
+}
+
+// ============================================================================
+// COUNTERPARTY LISTS
+// The DAO lists destination owners and target programs in per-address PDAs
+// at `[b"listing", guard, address]`. A denylisted counterparty is always
+// blocked; each KYA level can additionally require allowlisted ones.
+// ============================================================================
+
+/// DAO listing of a counterparty address
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum Listing {
+    #[default]
+    Unlisted,
+    Allowed,
+    Denied,
+}
+
+/// Listing of one destination owner or target program
+#[account]
+#[derive(InitSpace)]
+pub struct CounterpartyListing {
+    /// Guard this listing belongs to
+    pub guard: Pubkey,
+    /// Listed wallet or program
+    pub address: Pubkey,
+    pub listing: Listing,
+    /// Timestamp of the last change
+    pub updated_ts: i64,
+    pub bump: u8,
+}
+
+/// Allowlist requirements for one KYA level
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct CounterpartyRule {
+    /// Destination owner must be allowlisted
+    pub allowlisted_destination_only: bool,
+    /// Target program must be allowlisted
+    pub allowlisted_program_only: bool,
+}
+
+/// Who an evaluated amount goes to, as supplied by the caller
+/// Each key given here requires its listing PDA, initialised or not, so a
+/// denylisted counterparty cannot be hidden by omitting the account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct Counterparty {
+    /// Wallet that ends up owning the funds
+    pub destination_owner: Option<Pubkey>,
+    /// Program that moves the funds
+    pub target_program: Option<Pubkey>,
+}
+
+/// Listings resolved for a Counterparty (`None` = not supplied)
+#[derive(Clone, Copy, Debug, Default)]
+struct Listings {
+    destination: Option<Listing>,
+    target_program: Option<Listing>,
+}
+
+fn read_listings(
+    guard: &Pubkey,
+    counterparty: &Counterparty,
+    destination_listing: Option<&AccountInfo>,
+    program_listing: Option<&AccountInfo>,
+) -> Result<Listings> {
+    Ok(Listings {
+        destination: read_listing(guard, counterparty.destination_owner, destination_listing)?,
+        target_program: read_listing(guard, counterparty.target_program, program_listing)?,
+    })
+}
+
+/// Listing of `address`, or Unlisted if its PDA was never created
+fn read_listing(guard: &Pubkey, address: Option<Pubkey>, account: Option<&AccountInfo>) -> Result<Option<Listing>> {
+    let Some(address) = address else {
+        return Ok(None);
+    };
+    let account = account.ok_or(ErrorCode::MissingListingAccount)?;
+    let (expected, _) = Pubkey::find_program_address(&[b"listing", guard.as_ref(), address.as_ref()], &crate::ID);
+    require_keys_eq!(account.key(), expected, ErrorCode::ListingAccountMismatch);
+
+    if account.owner != &crate::ID || account.data_is_empty() {
+        return Ok(Some(Listing::Unlisted));
+    }
+    let entry = CounterpartyListing::try_deserialize(&mut &account.data.borrow()[..])?;
+    Ok(Some(entry.listing))
+}
+
+/// Owner field of an SPL / Token-2022 token account
+fn read_token_owner(token_account: &AccountInfo, token_program: &Pubkey) -> Result<Pubkey> {
+    require_keys_eq!(*token_account.owner, *token_program, ErrorCode::InvalidTreasuryTokenAccount);
+    let data = token_account.try_borrow_data()?;
+    require!(data.len() >= 64, ErrorCode::InvalidTreasuryTokenAccount);
+    Ok(Pubkey::new_from_array(data[32..64].try_into().unwrap()))
+}
+
+impl From<Listing> for core::Listing {
+    fn from(listing: Listing) -> Self {
+        match listing {
+            Listing::Unlisted => core::Listing::Unlisted,
+            Listing::Allowed => core::Listing::Allowed,
+            Listing::Denied => core::Listing::Denied,
+        }
+    }
+}
+
+impl From<core::Listing> for Listing {
+    fn from(listing: core::Listing) -> Self {
+        match listing {
+            core::Listing::Unlisted => Listing::Unlisted,
+            core::Listing::Allowed => Listing::Allowed,
+            core::Listing::Denied => Listing::Denied,
+        }
+    }
+}
+
+impl From<CounterpartyRule> for core::CounterpartyRule {
+    fn from(rule: CounterpartyRule) -> Self {
+        core::CounterpartyRule {
+            allowlisted_destination_only: rule.allowlisted_destination_only,
+            allowlisted_program_only: rule.allowlisted_program_only,
+        }
+    }
+}
+
+// ============================================================================
+// CALL CONTEXT (RULE_01)
+// ============================================================================
+
+/// Caller allowlist slots (Token-2022, transfer hook, routers)
+pub const MAX_ALLOWED_CALLERS: usize = 8;
+/// Deepest legitimate path: router -> Token-2022 -> transfer hook -> guard
+pub const MAX_CPI_STACK_HEIGHT: usize = TRANSACTION_LEVEL_STACK_HEIGHT + 3;
+
+/// RULE_01: reject proxy calls and accumulator games
+/// - Via CPI, the top-level instruction must target an allowlisted program
+///   and the call must not be nested deeper than MAX_CPI_STACK_HEIGHT.
+/// - No other top-level instruction in the transaction may evaluate the
+///   same guard.
+///
+/// Two CPI evaluations under the same top-level instruction are not visible
+/// here; the RULE_05 cooldown rejects the second one, since every approval
+/// restarts it and MIN_COOLDOWN_SECONDS keeps it non-zero.
+fn check_call_context(guard: &Account<GuardState>, ix_sysvar: &AccountInfo) -> Result<()> {
+    let current_index = load_current_index_checked(ix_sysvar)? as usize;
+    let current_ix = load_instruction_at_checked(current_index, ix_sysvar)?;
+    let stack_height = get_stack_height();
+
+    if stack_height > TRANSACTION_LEVEL_STACK_HEIGHT {
+        require!(stack_height <= MAX_CPI_STACK_HEIGHT, ErrorCode::CallDepthExceeded);
+        let caller = current_ix.program_id;
+        require!(
+            caller != Pubkey::default() && guard.allowed_callers.contains(&caller),
+            ErrorCode::CallerNotAllowed
+        );
+    } else {
+        // Top-level call: the current instruction must be this one
+        require_keys_eq!(current_ix.program_id, crate::ID, ErrorCode::UnexpectedInstructionPosition);
+    }
+
+    let guard_key = guard.key();
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, ix_sysvar) {
+        if index != current_index && ix.program_id == crate::ID {
+            let evaluates = ix.data.starts_with(&instruction::EvaluateTransaction::DISCRIMINATOR)
+                || ix.data.starts_with(&instruction::EvaluateTransactionMut::DISCRIMINATOR)
+                || ix.data.starts_with(&instruction::ExecuteTreasuryTransfer::DISCRIMINATOR)
+                || ix.data.starts_with(&instruction::EvaluateBuyback::DISCRIMINATOR);
+            require!(
+                !(evaluates && ix.accounts.iter().any(|a| a.pubkey == guard_key)),
+                ErrorCode::DuplicateEvaluation
+            );
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Top-level evaluations must be signed by the subject, a registered executor
+/// Otherwise anyone could fill the daily accumulator, restart the cooldown
+/// and trip the velocity breaker without moving funds. CPI evaluations are
+/// vetted by check_call_context instead.
+fn check_evaluator(guard: &GuardState, authority: Option<&Signer>, subject: &Pubkey) -> Result<()> {
+    if get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+    let authority = authority.ok_or(ErrorCode::UnauthorizedEvaluator)?.key();
+    require!(
+        authority != Pubkey::default() && authority == *subject && guard.executors.contains(&authority),
+        ErrorCode::UnauthorizedEvaluator
+    );
+    Ok(())
+}
+
+// ============================================================================
+// CIRCUIT BREAKER (Law 2)
+// "Emergency brake halts all operations if violated." Anomaly triggers set
+// `paused` on their own; only an admin quorum can release it, after a cooldown.
+// ============================================================================
+
+/// Monitor slots (keys allowed to call record_denial)
+pub const MAX_MONITORS: usize = 4;
+/// Upper bound on the unpause cooldown, so a bad config cannot brick the guard
+pub const MAX_UNPAUSE_COOLDOWN_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+/// Why the guard is paused
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum TripCause {
+    #[default]
+    None,
+    /// Admin called pause
+    Manual,
+    /// Too many denied evaluations within the denial window
+    Denials,
+    /// Approved spend within the velocity window exceeded the threshold
+    Velocity,
+    /// Treasury value fell too far between two valuations
+    ValueDrop,
+}
+
+/// Anomaly thresholds. A zero threshold disables that trigger.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct CircuitBreakerConfig {
+    /// Denied evaluations within `denial_window_seconds` that trip the breaker
+    pub max_denials: u16,
+    pub denial_window_seconds: u32,
+    /// Approved spend within `velocity_window_seconds`, as bps of treasury value
+    pub max_velocity_bps: u16,
+    pub velocity_window_seconds: u32,
+    /// Treasury value drop between two valuations, in bps
+    pub max_value_drop_bps: u16,
+    /// Minimum time between a pause and an Unpause change
+    pub unpause_cooldown_seconds: u32,
+}
+
+impl CircuitBreakerConfig {
+    /// 5 denials/hour, 1% per hour, 20% valuation drop, 1h unpause cooldown
+    pub const DEFAULT: Self = CircuitBreakerConfig {
+        max_denials: 5,
+        denial_window_seconds: 60 * 60,
+        max_velocity_bps: 100,
+        velocity_window_seconds: 60 * 60,
+        max_value_drop_bps: 2000,
+        unpause_cooldown_seconds: 60 * 60,
+    };
+}
+
+/// Breaker bookkeeping, cleared on unpause
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct CircuitBreakerState {
+    /// Cause of the current pause (None while running)
+    pub trip_cause: TripCause,
+    /// When the guard was paused
+    pub tripped_at: i64,
+    pub denial_count: u16,
+    pub denial_window_start: i64,
+    /// Approved spend in the current velocity window
+    pub velocity_spent: u64,
+    pub velocity_window_start: i64,
+}
+
+fn validate_breaker_config(config: &CircuitBreakerConfig) -> Result<()> {
+    require!(
+        config.max_denials == 0 || config.denial_window_seconds > 0,
+        ErrorCode::InvalidCircuitBreakerConfig
+    );
+    require!(
+        config.max_velocity_bps == 0 || config.velocity_window_seconds > 0,
+        ErrorCode::InvalidCircuitBreakerConfig
+    );
+    require!(
+        config.max_value_drop_bps as u64 <= core::BPS_DENOMINATOR,
+        ErrorCode::InvalidCircuitBreakerConfig
+    );
+    require!(
+        config.unpause_cooldown_seconds <= MAX_UNPAUSE_COOLDOWN_SECONDS,
+        ErrorCode::InvalidCircuitBreakerConfig
+    );
+    Ok(())
+}
+
+/// Pause the guard and record why. The first cause wins while paused.
+fn trip(guard: &mut Account<GuardState>, cause: TripCause, now: i64) {
+    if guard.paused {
+        return;
+    }
+    guard.paused = true;
+    guard.breaker.trip_cause = cause;
+    guard.breaker.tripped_at = now;
+    msg!("Circuit breaker tripped: {:?}", cause);
+    emit!(GuardPaused {
+        guard: guard.key(),
+        cause,
+        timestamp: now,
+    });
+}
+
+/// Count a denied evaluation; trips at `max_denials` within the window
+fn register_denial(guard: &mut Account<GuardState>, now: i64) {
+    let config = guard.breaker_config;
+    if config.max_denials == 0 {
+        return;
+    }
+
+    let breaker = &mut guard.breaker;
+    if now.saturating_sub(breaker.denial_window_start) >= config.denial_window_seconds as i64 {
+        breaker.denial_count = 0;
+        breaker.denial_window_start = now;
+    }
+    breaker.denial_count = breaker.denial_count.saturating_add(1);
+    let count = breaker.denial_count;
+
+    msg!("Denial recorded ({} / {})", count, config.max_denials);
+    if count >= config.max_denials {
+        trip(guard, TripCause::Denials, now);
+    }
+}
+
+/// Add approved spend to the velocity window; trips once it exceeds the
+/// threshold. The spend that trips the breaker still goes through.
+fn register_spend(guard: &mut Account<GuardState>, amount: u64, now: i64) -> Result<()> {
+    let config = guard.breaker_config;
+    if config.max_velocity_bps == 0 {
+        return Ok(());
+    }
+    let threshold = core::bps_of(guard.treasury_value, config.max_velocity_bps as u64)
+        .map_err(ErrorCode::from)?;
+
+    let breaker = &mut guard.breaker;
+    if now.saturating_sub(breaker.velocity_window_start) >= config.velocity_window_seconds as i64 {
+        breaker.velocity_spent = 0;
+        breaker.velocity_window_start = now;
+    }
+    breaker.velocity_spent = breaker.velocity_spent
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    if breaker.velocity_spent > threshold {
+        trip(guard, TripCause::Velocity, now);
+    }
+    Ok(())
+}
+
+/// Compare a new valuation with the previous one; trips on a sharp drop
+/// Skipped when the previous valuation was invalidated by an asset change.
+fn check_value_drop(guard: &mut Account<GuardState>, new_value: u64, now: i64) {
+    let max_drop_bps = guard.breaker_config.max_value_drop_bps;
+    let previous = guard.treasury_value;
+    if max_drop_bps == 0 || previous == 0 || guard.treasury_value_updated_ts == 0 || new_value >= previous {
+        return;
+    }
+
+    let drop_bps = (previous - new_value) as u128 * core::BPS_DENOMINATOR as u128 / previous as u128;
+    if drop_bps > max_drop_bps as u128 {
+        msg!("Treasury value dropped {} bps ({} -> {})", drop_bps, previous, new_value);
+        trip(guard, TripCause::ValueDrop, now);
+    }
+}
+
+// ============================================================================
+// VERDICT
+// ============================================================================
+
+/// A rule that blocked (or would block) a transaction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RuleViolation {
+    CooldownActive,
+    GuardPaused,
+    ValuationStale,
+    InvalidKyaLevel,
+    InsufficientKyaLevel,
+    KyaLimitExceeded,
+    MaxDrawdownExceeded,
+    DailyLimitExceeded,
+    PolicyLimitExceeded,
+    RevenueTrackerRequired,
+    RevenueCapExceeded,
+    VolumeFeedUnavailable,
+    BuybackCapExceeded,
+    DestinationDenied,
+    ProgramDenied,
+    DestinationNotAllowlisted,
+    ProgramNotAllowlisted,
+}
+
+impl RuleViolation {
+    pub fn error(&self) -> ErrorCode {
+        match self {
+            RuleViolation::GuardPaused => ErrorCode::GuardPaused,
+            RuleViolation::CooldownActive => ErrorCode::CooldownActive,
+            RuleViolation::ValuationStale => ErrorCode::ValuationStale,
+            RuleViolation::InvalidKyaLevel => ErrorCode::InvalidKyaLevel,
+            RuleViolation::InsufficientKyaLevel => ErrorCode::InsufficientKyaLevel,
+            RuleViolation::KyaLimitExceeded => ErrorCode::KyaLimitExceeded,
+            RuleViolation::MaxDrawdownExceeded => ErrorCode::MaxDrawdownExceeded,
+            RuleViolation::DailyLimitExceeded => ErrorCode::DailyLimitExceeded,
+            RuleViolation::PolicyLimitExceeded => ErrorCode::PolicyLimitExceeded,
+            RuleViolation::RevenueTrackerRequired => ErrorCode::RevenueTrackerRequired,
+            RuleViolation::RevenueCapExceeded => ErrorCode::RevenueCapExceeded,
+            RuleViolation::VolumeFeedUnavailable => ErrorCode::VolumeFeedUnavailable,
+            RuleViolation::BuybackCapExceeded => ErrorCode::BuybackCapExceeded,
+            RuleViolation::DestinationDenied => ErrorCode::DestinationDenied,
+            RuleViolation::ProgramDenied => ErrorCode::ProgramDenied,
+            RuleViolation::DestinationNotAllowlisted => ErrorCode::DestinationNotAllowlisted,
+            RuleViolation::ProgramNotAllowlisted => ErrorCode::ProgramNotAllowlisted,
+        }
+    }
+}
+
+/// Limits that applied to the evaluated amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct EffectiveLimits {
+    /// Per-transaction limit for the agent's KYA level
+    pub kya_tx_limit: u64,
+    /// Global max drawdown per transaction
+    pub max_drawdown: u64,
+    /// Daily spend limit
+    pub daily_limit: u64,
+    /// Custom max tx size (RULE_MAX_TX_SIZE), if enabled
+    pub policy_tx_limit: Option<u64>,
+    /// Prior-month revenue cap (RULE_03), if enabled and computable
+    pub revenue_tx_limit: Option<u64>,
+    /// Buyback allowance left today (RULE_04), for buybacks with a fresh feed
+    pub buyback_headroom: Option<u64>,
+}
+
+/// Structured result of a policy evaluation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct PolicyVerdict {
+    pub allowed: bool,
+    /// Every failed rule, in evaluation order
+    pub violations: Vec<RuleViolation>,
+    /// Daily spend still available before this transaction
+    pub daily_headroom: u64,
+    /// Seconds until the cooldown allows another operation
+    pub cooldown_remaining: u64,
+    pub limits: EffectiveLimits,
+    /// Listing of the destination owner, if one was supplied
+    pub destination_listing: Option<Listing>,
+    /// Listing of the target program, if one was supplied
+    pub program_listing: Option<Listing>,
+}
+
+impl From<core::Violation> for RuleViolation {
+    fn from(violation: core::Violation) -> Self {
+        match violation {
+            core::Violation::CooldownActive => RuleViolation::CooldownActive,
+            core::Violation::GuardPaused => RuleViolation::GuardPaused,
+            core::Violation::ValuationStale => RuleViolation::ValuationStale,
+            core::Violation::InvalidKyaLevel => RuleViolation::InvalidKyaLevel,
+            core::Violation::InsufficientKyaLevel => RuleViolation::InsufficientKyaLevel,
+            core::Violation::KyaLimitExceeded => RuleViolation::KyaLimitExceeded,
+            core::Violation::MaxDrawdownExceeded => RuleViolation::MaxDrawdownExceeded,
+            core::Violation::DailyLimitExceeded => RuleViolation::DailyLimitExceeded,
+            core::Violation::PolicyLimitExceeded => RuleViolation::PolicyLimitExceeded,
+            core::Violation::RevenueTrackerRequired => RuleViolation::RevenueTrackerRequired,
+            core::Violation::RevenueCapExceeded => RuleViolation::RevenueCapExceeded,
+            core::Violation::VolumeFeedUnavailable => RuleViolation::VolumeFeedUnavailable,
+            core::Violation::BuybackCapExceeded => RuleViolation::BuybackCapExceeded,
+            core::Violation::DestinationDenied => RuleViolation::DestinationDenied,
+            core::Violation::ProgramDenied => RuleViolation::ProgramDenied,
+            core::Violation::DestinationNotAllowlisted => RuleViolation::DestinationNotAllowlisted,
+            core::Violation::ProgramNotAllowlisted => RuleViolation::ProgramNotAllowlisted,
+        }
+    }
+}
+
+impl From<core::Verdict> for PolicyVerdict {
+    fn from(verdict: core::Verdict) -> Self {
+        PolicyVerdict {
+            allowed: verdict.allowed(),
+            violations: verdict.violations.iter().map(RuleViolation::from).collect(),
+            daily_headroom: verdict.daily_headroom,
+            cooldown_remaining: verdict.cooldown_remaining,
+            limits: EffectiveLimits {
+                kya_tx_limit: verdict.limits.kya_tx_limit,
+                max_drawdown: verdict.limits.max_drawdown,
+                daily_limit: verdict.limits.daily_limit,
+                policy_tx_limit: verdict.limits.policy_tx_limit,
+                revenue_tx_limit: verdict.limits.revenue_tx_limit,
+                buyback_headroom: verdict.limits.buyback_headroom,
+            },
+            destination_listing: verdict.destination.map(Listing::from),
+            program_listing: verdict.target_program.map(Listing::from),
+        }
+    }
+}
+
+impl From<core::MathOverflow> for ErrorCode {
+    fn from(_: core::MathOverflow) -> Self {
+        ErrorCode::Overflow
+    }
+}
+
+impl From<core::PriceError> for ErrorCode {
+    fn from(error: core::PriceError) -> Self {
+        match error {
+            core::PriceError::InvalidFeed => ErrorCode::InvalidPriceFeed,
+            core::PriceError::Unavailable => ErrorCode::PriceFeedUnavailable,
+            core::PriceError::Stale => ErrorCode::PriceFeedStale,
+            core::PriceError::ConfidenceTooWide => ErrorCode::PriceConfidenceTooWide,
+            core::PriceError::Overflow => ErrorCode::Overflow,
+        }
+    }
+}
+
+impl GuardState {
+    /// Inputs for grid_policy_core::evaluate
+    pub fn snapshot(&self, now: i64) -> core::GuardSnapshot {
+        core::GuardSnapshot {
+            paused: self.paused,
+            valuation_fresh: self.valuation_is_fresh(now),
+            treasury_value: self.treasury_value,
+            daily_spend_bps: self.daily_spend_bps,
+            max_drawdown_bps: self.max_drawdown_bps,
+            cooldown_seconds: self.cooldown_seconds,
+            last_operation_ts: self.last_operation_ts,
+            daily_spend_accumulator: self.daily_spend_accumulator,
+            last_reset_ts: self.last_reset_ts,
+            buyback_accumulator: self.buyback_accumulator,
+            counterparty_rules: self.counterparty_rules.map(core::CounterpartyRule::from),
+        }
+    }
+}
+
+/// External inputs for RULE_03 and RULE_04, from whichever accounts were passed
+fn rule_feeds(
+    revenue_tracker: Option<&RevenueTracker>,
+    volume_feed: Option<&VolumeFeed>,
+    now: i64,
+) -> Result<core::Feeds> {
+    Ok(core::Feeds {
+        revenue_average: revenue_tracker
+            .map(|tracker| tracker.prior_month_average(now))
+            .transpose()?,
+        daily_volume: volume_feed.and_then(|feed| feed.fresh_volume(now)),
+    })
+}
+
+/// Evaluate every rule against `amount` without mutating state
+fn build_verdict(
+    guard: &GuardState,
+    feeds: core::Feeds,
+    listings: Listings,
+    amount: u64,
+    agent_kya_level: u8,
+    operation: OperationKind,
+    now: i64,
+) -> Result<PolicyVerdict> {
+    let policies = guard.policies.map(|p| core::Rule {
+        rule_id: p.rule_id,
+        threshold_bps: p.threshold_bps,
+        enabled: p.enabled,
+    });
+    let request = core::Request {
+        amount,
+        agent_kya_level,
+        operation: operation.into(),
+        destination: listings.destination.map(core::Listing::from),
+        target_program: listings.target_program.map(core::Listing::from),
+        now,
+    };
+
+    let verdict = core::evaluate(&guard.snapshot(now), &policies, &feeds, &request)
+        .map_err(ErrorCode::from)?;
+    Ok(verdict.into())
+}
+
+/// Charge an approved amount to its daily accumulator and start the cooldown
+/// Returns the updated accumulator for `operation`.
+fn record_approval(
+    guard: &mut Account<GuardState>,
+    amount: u64,
+    operation: OperationKind,
+    now: i64,
+) -> Result<u64> {
+    if core::daily_window_elapsed(guard.last_reset_ts, now) {
+        guard.daily_spend_accumulator = 0;
+        guard.buyback_accumulator = 0;
+        guard.last_reset_ts = now;
+        msg!("Daily spend limit reset.");
+    }
+    let accumulator = match operation {
+        OperationKind::Spend => &mut guard.daily_spend_accumulator,
+        OperationKind::Buyback => &mut guard.buyback_accumulator,
+    };
+    *accumulator = accumulator.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let total = *accumulator;
+
+    guard.last_operation_ts = now as u64;
+    register_spend(guard, amount, now)?;
+    Ok(total)
+}
+
+// ============================================================================
+// AUDIT TRAIL
+// Events cover approvals, recorded denials and every configuration change.
+// Denied evaluations revert, so their record comes from record_denial; the
+// DecisionLog ring buffer keeps the last decisions on-chain for forensics.
+// ============================================================================
+
+/// Entries kept in the DecisionLog ring buffer
+pub const DECISION_LOG_LEN: usize = 32;
+
+#[event]
+pub struct GuardInitialized {
+    pub guard: Pubkey,
+    pub scope: Pubkey,
+    pub admins: Vec<Pubkey>,
+    pub admin_threshold: u8,
+    pub guardians: Vec<Pubkey>,
+}
+
+#[event]
+pub struct TransactionApproved {
+    pub guard: Pubkey,
+    pub subject: Pubkey,
+    pub amount: u64,
+    pub agent_kya_level: u8,
+    /// Daily accumulator after this spend
+    pub daily_spend: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DenialRecorded {
+    pub guard: Pubkey,
+    pub subject: Pubkey,
+    pub amount: u64,
+    pub agent_kya_level: u8,
+    pub violations: Vec<RuleViolation>,
+    pub monitor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryTransferExecuted {
+    pub guard: Pubkey,
+    pub executor: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    /// Base units moved
+    pub amount: u64,
+    /// Quote value charged to the limits
+    pub value: u64,
+    pub memo: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExposureOpened {
+    pub guard: Pubkey,
+    pub pool: Pubkey,
+    pub executor: Pubkey,
+    pub amount: u64,
+    /// Open exposure after this bet, for the pool and across pools
+    pub pool_exposure: u64,
+    pub total_exposure: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExposureReleased {
+    pub guard: Pubkey,
+    pub pool: Pubkey,
+    /// ReleaseExposure change that approved the release
+    pub change_id: u64,
+    pub amount: u64,
+    /// Reported payout on settlement, `None` for a cancellation
+    pub payout: Option<u64>,
+    /// Open exposure left, for the pool and across pools
+    pub pool_exposure: u64,
+    pub total_exposure: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryValueRefreshed {
+    pub guard: Pubkey,
+    pub treasury_value: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueRecorded {
+    pub guard: Pubkey,
+    pub reporter: Pubkey,
+    pub mint: Pubkey,
+    /// Base units of `mint` received
+    pub amount: u64,
+    /// Quote value added to the month's bucket
+    pub value: u64,
+    pub month: u32,
+    pub month_total: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VolumeFeedUpdated {
+    pub guard: Pubkey,
+    pub reporter: Pubkey,
+    pub daily_volume: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardPaused {
+    pub guard: Pubkey,
+    pub cause: TripCause,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub guard: Pubkey,
+    pub change_id: u64,
+    pub proposer: Pubkey,
+    pub change: GuardChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ChangeVoted {
+    pub guard: Pubkey,
+    pub change_id: u64,
+    pub admin: Pubkey,
+    pub approve: bool,
+    pub approvals: u8,
+    pub rejections: u8,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub guard: Pubkey,
+    pub change_id: u64,
+    pub change: GuardChange,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub guard: Pubkey,
+    pub change_id: u64,
+    pub authority: Pubkey,
+}
+
+/// Ring buffer of the most recent guard decisions
+#[account]
+#[derive(InitSpace)]
+pub struct DecisionLog {
+    /// Guard this log belongs to
+    pub guard: Pubkey,
+    /// Decisions recorded so far; the next one goes to `total % DECISION_LOG_LEN`
+    pub total: u64,
+    pub entries: [DecisionEntry; DECISION_LOG_LEN],
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct DecisionEntry {
+    /// Agent or wallet the decision was about
+    pub subject: Pubkey,
+    pub amount: u64,
+    /// First failed rule, or None for an approval
+    pub violation: Option<RuleViolation>,
+    pub timestamp: i64,
+}
+
+impl DecisionLog {
+    pub fn record(&mut self, entry: DecisionEntry) {
+        self.entries[(self.total % DECISION_LOG_LEN as u64) as usize] = entry;
+        self.total = self.total.wrapping_add(1);
+    }
+}
+
+/// Emit the approval event and append it to the decision log, if supplied
+fn log_approval(
+    guard: &Account<GuardState>,
+    decision_log: Option<&mut DecisionLog>,
+    subject: Pubkey,
+    amount: u64,
+    agent_kya_level: u8,
+    daily_spend: u64,
+    now: i64,
+) {
+    if let Some(log) = decision_log {
+        log.record(DecisionEntry {
+            subject,
+            amount,
+            violation: None,
+            timestamp: now,
+        });
+    }
+    emit!(TransactionApproved {
+        guard: guard.key(),
+        subject,
+        amount,
+        agent_kya_level,
+        daily_spend,
+        timestamp: now,
+    });
+}
+
+// ============================================================================
+// STATE
+// ============================================================================
+
+#[account]
+pub struct GuardState {
+    /// DAO admin set; `admin_threshold` of them must approve a change
+    pub admins: [Pubkey; MAX_ADMINS],
+    /// Approvals required to execute a PendingChange
+    pub admin_threshold: u8,
+    /// Bumped whenever the admin set changes
+    pub admin_set_version: u32,
+    /// Keys that may pause but never unpause or change rules
+    pub guardians: [Pubkey; MAX_GUARDIANS],
+    /// Emergency stop flag
+    pub paused: bool,
+    /// Max daily spend in basis points (0.5% = 50 bps)
+    pub daily_spend_bps: u16,
+    /// Max single transaction drawdown in bps (5% = 500 bps)
+    pub max_drawdown_bps: u16,
+    /// Cooldown between operations in seconds
+    pub cooldown_seconds: u32,
+    /// Timestamp of last treasury operation
+    pub last_operation_ts: u64,
+    /// Agent Identity program ID for KYA verification
+    pub agent_identity_program: Pubkey,
+    /// Treasury value for limit calculations, in quote units (USD, 6 decimals)
+    /// Computed by refresh_treasury_value from registered assets.
+    pub treasury_value: u64,
+    /// Daily spend accumulator (resets every 24h)
+    pub daily_spend_accumulator: u64,
+    /// Timestamp of the last daily reset
+    pub last_reset_ts: i64,
+    /// Active Policies (Replacing reserved space with 8 fixed slots)
+    pub policies: [PolicyRule; 8],
+    /// Minimum delay between queueing and executing a change (Law 1)
+    pub timelock_seconds: u32,
+    /// Monotonic counter used to derive PendingChange addresses
+    pub change_count: u64,
+    /// Timestamp of the last successful valuation
+    pub treasury_value_updated_ts: i64,
+    /// Treasury token accounts priced by refresh_treasury_value
+    pub treasury_assets: [TreasuryAsset; MAX_TREASURY_ASSETS],
+    /// Keys allowed to call record_revenue (default = empty slot)
+    pub revenue_reporters: [Pubkey; MAX_REVENUE_REPORTERS],
+    /// Top-level programs allowed to evaluate via CPI (RULE_01)
+    pub allowed_callers: [Pubkey; MAX_ALLOWED_CALLERS],
+    /// Keys allowed to call record_denial (default = empty slot)
+    pub monitors: [Pubkey; MAX_MONITORS],
+    /// Circuit breaker thresholds (Law 2)
+    pub breaker_config: CircuitBreakerConfig,
+    /// Circuit breaker windows and trip record
+    pub breaker: CircuitBreakerState,
+    /// Treasury or mint this guard instance protects (PDA seed)
+    pub scope: Pubkey,
+    pub bump: u8,
+    /// Keys allowed to call execute_treasury_transfer
+    pub executors: [Pubkey; MAX_EXECUTORS],
+    /// Where execute_treasury_transfer may send funds
+    pub treasury_destinations: [Pubkey; MAX_TREASURY_DESTINATIONS],
+    /// Key allowed to update the volume feed (RULE_04)
+    pub volume_reporter: Pubkey,
+    /// Buybacks approved in the current daily window (RULE_04)
+    pub buyback_accumulator: u64,
+    /// Counterparty allowlist requirements, indexed by KYA level
+    pub counterparty_rules: [CounterpartyRule; 4],
+    /// Max open exposure per betting pool, bps of treasury value (Law 2)
+    pub pool_exposure_bps: u16,
+    /// Open exposure across all betting pools, in quote units
+    pub open_exposure: u64,
+}
+
+/// Gatekeeper for guard instances, one per program
+#[account]
+#[derive(InitSpace)]
+pub struct GuardRegistry {
+    /// Only key allowed to create guard instances
+    pub authority: Pubkey,
+    /// Guard instances created so far
+    pub guard_count: u64,
+    pub bump: u8,
+}
+
+impl GuardState {
+    pub fn valuation_is_fresh(&self, now: i64) -> bool {
+        self.treasury_value_updated_ts > 0
+            && now.saturating_sub(self.treasury_value_updated_ts) <= MAX_VALUATION_AGE_SECONDS
+    }
+}
+
+/// A treasury token account and the feed used to price it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct TreasuryAsset {
+    /// Token account holding the asset (default = empty slot)
+    pub token_account: Pubkey,
+    /// Mint the token account must hold
+    pub mint: Pubkey,
+    /// Mint decimals, used to scale balances
+    pub decimals: u8,
+    /// Pyth-compatible price account quoting the asset in USD
+    pub price_feed: Pubkey,
+    /// Maximum age of the feed's publish time
+    pub max_staleness_seconds: u32,
+    /// Maximum confidence interval as bps of price
+    pub max_confidence_bps: u16,
+}
+
+impl TreasuryAsset {
+    pub fn is_registered(&self) -> bool {
+        self.token_account != Pubkey::default()
+    }
+
+    pub fn price_tolerance(&self) -> PriceTolerance {
+        PriceTolerance {
+            max_staleness_seconds: self.max_staleness_seconds,
+            max_confidence_bps: self.max_confidence_bps,
+        }
+    }
+}
+
+/// A guard parameter change waiting out the timelock
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    /// Guard this change applies to
+    pub guard: Pubkey,
+    /// Sequence number assigned at queue time
+    pub change_id: u64,
+    pub change: GuardChange,
+    /// Timestamp the change was queued
+    pub queued_at: i64,
+    /// Earliest execution timestamp requested by the DAO
+    pub eta: i64,
+    /// Admin that queued the change, refunded on close
+    pub proposer: Pubkey,
+    /// Admin set the approvals below refer to
+    pub admin_set_version: u32,
+    /// Bitmap of approving admin slots
+    pub approvals: u8,
+    /// Bitmap of rejecting admin slots
+    pub rejections: u8,
+    pub bump: u8,
+}
+
+/// Every admin action goes through the proposal queue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub enum GuardChange {
+    /// Add or update a policy rule
+    AddPolicy(PolicyRule),
+    /// Register (or update) a treasury token account and its price feed
+    SetTreasuryAsset(TreasuryAsset),
+    /// Stop counting a treasury token account towards treasury value
+    RemoveTreasuryAsset { token_account: Pubkey },
+    /// Allow or revoke a revenue reporter (signer key or program PDA)
+    SetRevenueReporter { reporter: Pubkey, allowed: bool },
+    /// Allow or revoke a top-level program that may reach the guard via CPI
+    SetAllowedCaller { program: Pubkey, allowed: bool },
+    /// Allow or revoke a key that reports denied evaluations
+    SetMonitor { monitor: Pubkey, allowed: bool },
+    /// Replace the circuit breaker thresholds
+    SetCircuitBreaker(CircuitBreakerConfig),
+    /// Set the daily spend cap (0.1% - 2.0%)
+    SetDailySpendBps { bps: u16 },
+    /// Set the single transaction cap (1% - 10%)
+    SetMaxDrawdownBps { bps: u16 },
+    /// Set the cooldown between treasury operations (1h - 24h)
+    SetCooldownSeconds { seconds: u32 },
+    /// Change the timelock duration (24h - 168h)
+    SetTimelock { seconds: u32 },
+    /// Replace the admin set and approval threshold
+    SetAdmins { admins: [Pubkey; MAX_ADMINS], threshold: u8 },
+    /// Allow or revoke a pause guardian
+    SetGuardian { guardian: Pubkey, allowed: bool },
+    /// Resume operations after a pause (quorum only, no timelock)
+    Unpause,
+    /// Allow or revoke a key that may request treasury transfers
+    SetExecutor { executor: Pubkey, allowed: bool },
+    /// Allow or revoke a treasury transfer destination
+    SetTreasuryDestination { destination: Pubkey, allowed: bool },
+    /// Set the volume feed reporter (default key disables updates)
+    SetVolumeReporter { reporter: Pubkey },
+    /// List a destination owner or target program (execute_listing_change)
+    SetCounterpartyListing { address: Pubkey, listing: Listing },
+    /// Set the allowlist requirements for one KYA level
+    SetCounterpartyRule { kya_level: u8, rule: CounterpartyRule },
+    /// Set the per-pool betting exposure cap (1% - 10%)
+    SetPoolExposureBps { bps: u16 },
+    /// Track treasury transfers to `pool` as exposure (execute_pool_change)
+    RegisterBettingPool { pool: Pubkey },
+    /// Release exposure on a settled bet, or a cancelled one (`payout` None)
+    /// (execute_pool_change, quorum only)
+    ReleaseExposure { pool: Pubkey, amount: u64, payout: Option<u64> },
+}
+
+impl GuardChange {
+    /// Unpause, denylisting and exposure releases only wait for the quorum
+    pub fn requires_timelock(&self) -> bool {
+        !matches!(
+            self,
+            GuardChange::Unpause
+                | GuardChange::SetCounterpartyListing { listing: Listing::Denied, .. }
+                | GuardChange::ReleaseExposure { .. }
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GuardConfig {
+    pub admins: Vec<Pubkey>,
+    pub admin_threshold: u8,
+    pub guardians: Vec<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct PolicyRule {
+    pub rule_id: u8,
+    pub threshold_bps: u16,
+    pub enabled: bool,
+}
+
+// ============================================================================
+// CONTEXTS
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + GuardRegistry::INIT_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, GuardRegistry>,
+
+    /// Only the upgrade authority can create the registry
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::PolicyGuard>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(payer.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, GuardRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(scope: Pubkey)]
+pub struct InitializeGuard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, GuardRegistry>,
+
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<GuardState>(),
+        seeds = [b"guard", scope.as_ref()],
+        bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + PendingChange::INIT_SPACE,
+        seeds = [b"change", guard_state.key().as_ref(), &guard_state.change_count.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// Must be in the admin set
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteOnChange<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        seeds = [b"change", guard_state.key().as_ref(), &pending_change.change_id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// Must be in the admin set
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"change", guard_state.key().as_ref(), &pending_change.change_id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct ExecuteListingChange<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"change", guard_state.key().as_ref(), &pending_change.change_id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + CounterpartyListing::INIT_SPACE,
+        seeds = [b"listing", guard_state.key().as_ref(), address.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, CounterpartyListing>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool: Pubkey)]
+pub struct ExecutePoolChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"change", guard_state.key().as_ref(), &pending_change.change_id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BettingPool::INIT_SPACE,
+        seeds = [b"pool", guard_state.key().as_ref(), pool.as_ref()],
+        bump
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"change", guard_state.key().as_ref(), &pending_change.change_id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(mut, address = pending_change.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRevenueTracker<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RevenueTracker::INIT_SPACE,
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump
+    )]
+    pub revenue_tracker: Account<'info, RevenueTracker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordRevenue<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Account<'info, RevenueTracker>,
+
+    /// CHECK: Must match the price feed registered for `mint`
+    pub price_feed: UncheckedAccount<'info>,
+
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVolumeFeed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VolumeFeed::INIT_SPACE,
+        seeds = [b"volume", guard_state.key().as_ref()],
+        bump
+    )]
+    pub volume_feed: Account<'info, VolumeFeed>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVolumeFeed<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        mut,
+        seeds = [b"volume", guard_state.key().as_ref()],
+        bump = volume_feed.bump
+    )]
+    pub volume_feed: Account<'info, VolumeFeed>,
+
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EvaluateBuyback<'info> {
     #[account(
         mut,
-        seeds = [b"guard"],
-        bump,
-        has_one = admin
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
     )]
     pub guard_state: Account<'info, GuardState>,
-    
-    pub admin: Signer<'info>,
+
+    /// Required when RULE_03 (revenue cap) is enabled
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    #[account(
+        seeds = [b"volume", guard_state.key().as_ref()],
+        bump = volume_feed.bump
+    )]
+    pub volume_feed: Account<'info, VolumeFeed>,
+
+    /// Optional audit ring buffer
+    #[account(
+        mut,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump = decision_log.bump
+    )]
+    pub decision_log: Option<Account<'info, DecisionLog>>,
+
+    /// CHECK: Listing PDA of `counterparty.destination_owner`, checked in the handler
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Listing PDA of `counterparty.target_program`, checked in the handler
+    pub program_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, used for RULE_01 introspection
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The subject, a registered executor; required for top-level calls
+    pub authority: Option<Signer<'info>>,
 }
 
 #[derive(Accounts)]
 pub struct EvaluateTx<'info> {
     #[account(
         mut,
-        seeds = [b"guard"],
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    /// Required when RULE_03 (revenue cap) is enabled
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    /// Optional audit ring buffer
+    #[account(
+        mut,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump = decision_log.bump
+    )]
+    pub decision_log: Option<Account<'info, DecisionLog>>,
+
+    /// CHECK: Listing PDA of `counterparty.destination_owner`, checked in the handler
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Listing PDA of `counterparty.target_program`, checked in the handler
+    pub program_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, used for RULE_01 introspection
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The subject, a registered executor; required for top-level calls
+    pub authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SimulateTx<'info> {
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    /// Required to evaluate RULE_03 (revenue cap)
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    /// Required to evaluate buybacks (RULE_04)
+    #[account(
+        seeds = [b"volume", guard_state.key().as_ref()],
+        bump = volume_feed.bump
+    )]
+    pub volume_feed: Option<Account<'info, VolumeFeed>>,
+
+    /// CHECK: Listing PDA of `counterparty.destination_owner`, checked in the handler
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Listing PDA of `counterparty.target_program`, checked in the handler
+    pub program_listing: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTreasuryTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    /// Required when RULE_03 (revenue cap) is enabled
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    /// Optional audit ring buffer
+    #[account(
+        mut,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump = decision_log.bump
+    )]
+    pub decision_log: Option<Account<'info, DecisionLog>>,
+
+    /// Owns the treasury token accounts and holds native SOL
+    #[account(
+        mut,
+        seeds = [b"treasury-vault", guard_state.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: SystemAccount<'info>,
+
+    /// CHECK: Registered treasury token account (omit for native SOL)
+    #[account(mut)]
+    pub source: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Must match the price feed registered for the source asset
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// CHECK: Must be an allowlisted destination, checked in the handler
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: Listing PDA of the destination owner, checked in the handler
+    pub destination_listing: UncheckedAccount<'info>,
+
+    /// CHECK: Listing PDA of the token or system program, checked in the handler
+    pub program_listing: UncheckedAccount<'info>,
+
+    /// CHECK: BettingPool PDA of the destination owner, checked in the handler
+    #[account(mut)]
+    pub betting_pool: UncheckedAccount<'info>,
+
+    /// Required for token transfers
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    /// Required when a memo is attached
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    pub executor: Signer<'info>,
+
+    /// CHECK: Executor's agent identity PDA, read in the handler (missing = KYA 0)
+    #[account(
+        seeds = [b"agent-id", executor.key().as_ref()],
+        seeds::program = guard_state.agent_identity_program,
         bump
     )]
+    pub executor_identity: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, used for RULE_01 introspection
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordDenial<'info> {
+    #[account(
+        mut,
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
     pub guard_state: Account<'info, GuardState>,
+
+    /// Required to re-evaluate RULE_03 (revenue cap)
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    /// Required to evaluate buybacks (RULE_04)
+    #[account(
+        seeds = [b"volume", guard_state.key().as_ref()],
+        bump = volume_feed.bump
+    )]
+    pub volume_feed: Option<Account<'info, VolumeFeed>>,
+
+    /// Optional audit ring buffer
+    #[account(
+        mut,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump = decision_log.bump
+    )]
+    pub decision_log: Option<Account<'info, DecisionLog>>,
+
+    /// CHECK: Listing PDA of `counterparty.destination_owner`, checked in the handler
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Listing PDA of `counterparty.target_program`, checked in the handler
+    pub program_listing: Option<UncheckedAccount<'info>>,
+
+    pub monitor: Signer<'info>,
 }
 
 // Added Mutable Context for actual execution
@@ -400,24 +3145,75 @@ pub struct EvaluateTx<'info> {
 pub struct EvaluateTxMut<'info> {
     #[account(
         mut,
-        seeds = [b"guard"],
-        bump
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
     )]
     pub guard_state: Account<'info, GuardState>,
+
+    /// Required when RULE_03 (revenue cap) is enabled
+    #[account(
+        seeds = [b"revenue", guard_state.key().as_ref()],
+        bump = revenue_tracker.bump
+    )]
+    pub revenue_tracker: Option<Account<'info, RevenueTracker>>,
+
+    /// Optional audit ring buffer
+    #[account(
+        mut,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump = decision_log.bump
+    )]
+    pub decision_log: Option<Account<'info, DecisionLog>>,
+
+    /// CHECK: Listing PDA of `counterparty.destination_owner`, checked in the handler
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Listing PDA of `counterparty.target_program`, checked in the handler
+    pub program_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, used for RULE_01 introspection
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The subject, a registered executor; required for top-level calls
+    pub authority: Option<Signer<'info>>,
 }
 
 
 #[derive(Accounts)]
-pub struct AdminOnly<'info> {
+pub struct InitializeDecisionLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
+    )]
+    pub guard_state: Account<'info, GuardState>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DecisionLog::INIT_SPACE,
+        seeds = [b"decisions", guard_state.key().as_ref()],
+        bump
+    )]
+    pub decision_log: Account<'info, DecisionLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PauseGuard<'info> {
     #[account(
         mut,
-        seeds = [b"guard"],
-        bump,
-        has_one = admin
+        seeds = [b"guard", guard_state.scope.as_ref()],
+        bump = guard_state.bump
     )]
     pub guard_state: Account<'info, GuardState>,
     
-    pub admin: Signer<'info>,
+    /// Any admin or guardian
+    pub authority: Signer<'info>,
 }
 
 // ============================================================================
@@ -446,4 +3242,529 @@ pub enum ErrorCode {
     PolicyLimitExceeded,
     #[msg("Math overflow occurred")]
     Overflow,
+    #[msg("Change ETA is earlier than the constitutional timelock allows")]
+    TimelockTooShort,
+    #[msg("Timelock has not elapsed for this change")]
+    TimelockNotElapsed,
+    #[msg("Queued change expired - cancel and queue it again")]
+    ChangeExpired,
+    #[msg("Timelock must be between 24h and 168h")]
+    TimelockOutOfBounds,
+    #[msg("Daily spend cap must be between 0.1% and 2.0% (Constitution, Law 2)")]
+    DailySpendOutOfBounds,
+    #[msg("Single transaction cap must be between 1% and 10% (Constitution, Law 2)")]
+    MaxDrawdownOutOfBounds,
+    #[msg("Cooldown must be between 1h and 24h")]
+    CooldownOutOfBounds,
+    #[msg("Treasury valuation is stale - call refresh_treasury_value")]
+    ValuationStale,
+    #[msg("No treasury assets registered for valuation")]
+    NoTreasuryAssets,
+    #[msg("Missing token account or price feed for a registered treasury asset")]
+    MissingValuationAccount,
+    #[msg("Valuation account does not match the registered treasury asset")]
+    ValuationAccountMismatch,
+    #[msg("Treasury asset registration is invalid")]
+    InvalidTreasuryAsset,
+    #[msg("Treasury asset storage is full")]
+    TreasuryAssetStorageFull,
+    #[msg("Treasury asset is not registered")]
+    TreasuryAssetNotFound,
+    #[msg("Treasury token account is invalid")]
+    InvalidTreasuryTokenAccount,
+    #[msg("Price feed account has an unexpected layout")]
+    InvalidPriceFeed,
+    #[msg("Price feed is not trading")]
+    PriceFeedUnavailable,
+    #[msg("Price feed is stale")]
+    PriceFeedStale,
+    #[msg("Price feed confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Signer is not a whitelisted revenue reporter")]
+    UnauthorizedRevenueReporter,
+    #[msg("Revenue amount must be greater than zero")]
+    ZeroRevenue,
+    #[msg("Revenue reporter storage is full")]
+    RevenueReporterStorageFull,
+    #[msg("Revenue tracker account required for RULE_03")]
+    RevenueTrackerRequired,
+    #[msg("Transaction exceeds prior-month revenue cap (RULE_03)")]
+    RevenueCapExceeded,
+    #[msg("Revenue cap must be between 1% and 10% (Constitution, Law 2)")]
+    RevenueCapOutOfBounds,
+    #[msg("vCPI blocked: calling program is not whitelisted (RULE_01)")]
+    CallerNotAllowed,
+    #[msg("vCPI blocked: call stack too deep (RULE_01)")]
+    CallDepthExceeded,
+    #[msg("vCPI blocked: unexpected instruction position (RULE_01)")]
+    UnexpectedInstructionPosition,
+    #[msg("Guard evaluated more than once in this transaction (RULE_01)")]
+    DuplicateEvaluation,
+    #[msg("Caller allowlist is full")]
+    AllowedCallerStorageFull,
+    #[msg("Policy rule ID 0 is reserved for empty slots")]
+    InvalidPolicyRule,
+    #[msg("Signer is not a registered monitor")]
+    UnauthorizedMonitor,
+    #[msg("Monitor storage is full")]
+    MonitorStorageFull,
+    #[msg("Evaluation would be allowed - nothing to record")]
+    EvaluationNotDenied,
+    #[msg("Circuit breaker configuration is invalid")]
+    InvalidCircuitBreakerConfig,
+    #[msg("Unpause cooldown is still active")]
+    UnpauseCooldownActive,
+    #[msg("Signer is not in the admin set")]
+    NotAnAdmin,
+    #[msg("Signer is not an admin or guardian")]
+    UnauthorizedPauser,
+    #[msg("Admin set is full")]
+    AdminStorageFull,
+    #[msg("Guardian storage is full")]
+    GuardianStorageFull,
+    #[msg("Admin set contains a duplicate key")]
+    DuplicateAdmin,
+    #[msg("Admin threshold must be between 1 and the number of admins")]
+    InvalidAdminThreshold,
+    #[msg("Not enough admin approvals to execute this change")]
+    InsufficientApprovals,
+    #[msg("Admin set changed since this change was queued - queue it again")]
+    StaleAdminSet,
+    #[msg("Only the proposer can cancel a change that can still pass")]
+    ChangeStillPending,
+    #[msg("Policy Guard is not paused")]
+    GuardNotPaused,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Signer is not an allowed treasury executor")]
+    UnauthorizedExecutor,
+    #[msg("Executor storage is full")]
+    ExecutorStorageFull,
+    #[msg("Destination is not on the treasury allowlist")]
+    DestinationNotAllowed,
+    #[msg("Treasury destination storage is full")]
+    DestinationStorageFull,
+    #[msg("Memo is too long")]
+    MemoTooLong,
+    #[msg("Transfer amount must be greater than zero")]
+    ZeroTransferAmount,
+    #[msg("Missing account required for this treasury transfer")]
+    MissingTransferAccount,
+    #[msg("Signer is not the volume reporter")]
+    UnauthorizedVolumeReporter,
+    #[msg("Daily volume feed is missing or stale (RULE_04)")]
+    VolumeFeedUnavailable,
+    #[msg("Buyback exceeds 2% of daily volume (RULE_04)")]
+    BuybackCapExceeded,
+    #[msg("Destination owner is denylisted")]
+    DestinationDenied,
+    #[msg("Target program is denylisted")]
+    ProgramDenied,
+    #[msg("Destination owner is not allowlisted for this KYA level")]
+    DestinationNotAllowlisted,
+    #[msg("Target program is not allowlisted for this KYA level")]
+    ProgramNotAllowlisted,
+    #[msg("Listing account required for the supplied counterparty")]
+    MissingListingAccount,
+    #[msg("Listing account does not match the counterparty")]
+    ListingAccountMismatch,
+    #[msg("Listing changes use execute_listing_change, all others execute_change")]
+    WrongExecuteInstruction,
+    #[msg("Exposure amount must be greater than zero")]
+    ZeroExposureAmount,
+    #[msg("Bet exceeds the per-pool exposure cap (Law 2)")]
+    PoolExposureExceeded,
+    #[msg("Bet exceeds the aggregate betting exposure cap")]
+    TotalExposureExceeded,
+    #[msg("Released amount exceeds open exposure")]
+    ExposureUnderflow,
+    #[msg("Per-pool exposure cap must be between 1% and 10% (Constitution, Law 2)")]
+    ExposureCapOutOfBounds,
+    #[msg("Betting pool account does not match the destination owner")]
+    BettingPoolAccountMismatch,
+    #[msg("Betting pool is already registered")]
+    BettingPoolAlreadyRegistered,
+    #[msg("Betting pool is not registered")]
+    BettingPoolNotRegistered,
+    #[msg("Top-level evaluations must be signed by the subject, a registered executor")]
+    UnauthorizedEvaluator,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A zeroed guard with the default breaker thresholds
+    fn guard() -> GuardState {
+        let data = vec![0u8; 8 + std::mem::size_of::<GuardState>()];
+        let mut guard = GuardState::try_deserialize_unchecked(&mut data.as_slice()).unwrap();
+        guard.breaker_config = CircuitBreakerConfig::DEFAULT;
+        guard
+    }
+
+    /// Backing storage for an AccountInfo owned by `owner`
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        is_signer: bool,
+    }
+
+    impl TestAccount {
+        fn guard(state: &GuardState) -> Self {
+            Self::program(Pubkey::new_unique(), state)
+        }
+
+        /// An account of this program holding `state` at `key`
+        fn program<T: AccountSerialize>(key: Pubkey, state: &T) -> Self {
+            let mut data = Vec::new();
+            state.try_serialize(&mut data).unwrap();
+            TestAccount { key, owner: crate::ID, lamports: 0, data, is_signer: false }
+        }
+
+        fn signer(key: Pubkey) -> Self {
+            TestAccount { is_signer: true, ..Self::uninitialized(key) }
+        }
+
+        fn uninitialized(key: Pubkey) -> Self {
+            TestAccount { key, owner: system_program::ID, lamports: 0, data: Vec::new(), is_signer: false }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(&self.key, self.is_signer, true, &mut self.lamports, &mut self.data, &self.owner, false, 0)
+        }
+    }
+
+    #[test]
+    fn unsigned_top_level_evaluation_is_rejected() {
+        let executor = Pubkey::new_unique();
+        let mut guard = guard();
+        guard.executors[0] = executor;
+
+        assert_eq!(
+            check_evaluator(&guard, None, &executor).unwrap_err(),
+            ErrorCode::UnauthorizedEvaluator.into()
+        );
+    }
+
+    #[test]
+    fn top_level_evaluation_needs_the_subject_as_executor() {
+        let executor = Pubkey::new_unique();
+        let mut guard = guard();
+        guard.executors[0] = executor;
+
+        let mut account = TestAccount::signer(executor);
+        let info = account.info();
+        let signer = Signer::try_from(&info).unwrap();
+        assert!(check_evaluator(&guard, Some(&signer), &executor).is_ok());
+        // Signed by the executor on behalf of someone else
+        assert_eq!(
+            check_evaluator(&guard, Some(&signer), &Pubkey::new_unique()).unwrap_err(),
+            ErrorCode::UnauthorizedEvaluator.into()
+        );
+
+        let outsider = Pubkey::new_unique();
+        let mut account = TestAccount::signer(outsider);
+        let info = account.info();
+        let signer = Signer::try_from(&info).unwrap();
+        assert_eq!(
+            check_evaluator(&guard, Some(&signer), &outsider).unwrap_err(),
+            ErrorCode::UnauthorizedEvaluator.into()
+        );
+    }
+
+    #[test]
+    fn breaker_trips_at_max_denials() {
+        let mut account = TestAccount::guard(&guard());
+        let info = account.info();
+        let mut guard = Account::<GuardState>::try_from(&info).unwrap();
+        let max_denials = guard.breaker_config.max_denials;
+
+        for _ in 1..max_denials {
+            register_denial(&mut guard, 1_000);
+        }
+        assert!(!guard.paused);
+        register_denial(&mut guard, 1_000);
+        assert!(guard.paused);
+        assert_eq!(guard.breaker.trip_cause, TripCause::Denials);
+        assert_eq!(guard.breaker.tripped_at, 1_000);
+    }
+
+    #[test]
+    fn denials_outside_the_window_do_not_trip() {
+        let mut account = TestAccount::guard(&guard());
+        let info = account.info();
+        let mut guard = Account::<GuardState>::try_from(&info).unwrap();
+        let config = guard.breaker_config;
+
+        // One short of the limit, then the window rolls over
+        let start = config.denial_window_seconds as i64;
+        for _ in 1..config.max_denials {
+            register_denial(&mut guard, start);
+        }
+        register_denial(&mut guard, start * 2);
+        assert!(!guard.paused);
+        assert_eq!(guard.breaker.denial_count, 1);
+    }
+
+    #[test]
+    fn unpause_waits_for_the_cooldown_and_resets_the_breaker() {
+        let mut guard = guard();
+        guard.paused = true;
+        guard.breaker.trip_cause = TripCause::Velocity;
+        guard.breaker.tripped_at = 1_000;
+        guard.breaker.velocity_spent = 42;
+        let unlock_ts = 1_000 + guard.breaker_config.unpause_cooldown_seconds as i64;
+
+        assert_eq!(
+            apply_change(&mut guard, GuardChange::Unpause, unlock_ts - 1).unwrap_err(),
+            ErrorCode::UnpauseCooldownActive.into()
+        );
+        assert!(guard.paused);
+
+        apply_change(&mut guard, GuardChange::Unpause, unlock_ts).unwrap();
+        assert!(!guard.paused);
+        assert_eq!(guard.breaker.trip_cause, TripCause::None);
+        assert_eq!(guard.breaker.velocity_spent, 0);
+        assert_eq!(
+            apply_change(&mut guard, GuardChange::Unpause, unlock_ts).unwrap_err(),
+            ErrorCode::GuardNotPaused.into()
+        );
+    }
+
+    fn betting_pool(guard: &Pubkey, pool: &Pubkey) -> TestAccount {
+        let (key, _) = Pubkey::find_program_address(&[b"pool", guard.as_ref(), pool.as_ref()], &crate::ID);
+        let state = BettingPool {
+            guard: *guard,
+            pool: *pool,
+            open_exposure: 0,
+            total_opened: 0,
+            total_payout: 0,
+            bump: 0,
+        };
+        TestAccount::program(key, &state)
+    }
+
+    fn open_exposure(account: &TestAccount) -> u64 {
+        BettingPool::try_deserialize(&mut account.data.as_slice()).unwrap().open_exposure
+    }
+
+    #[test]
+    fn exposure_is_capped_per_pool_and_across_pools() {
+        let mut state = guard();
+        state.treasury_value = 1_000_000;
+        state.pool_exposure_bps = DEFAULT_POOL_EXPOSURE_BPS;
+        let mut account = TestAccount::guard(&state);
+        let info = account.info();
+        let mut guard = Account::<GuardState>::try_from(&info).unwrap();
+        let executor = Pubkey::new_unique();
+
+        // 5% per pool, 15% in total
+        let pools: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut accounts: Vec<TestAccount> = pools.iter().map(|pool| betting_pool(&guard.key(), pool)).collect();
+
+        record_exposure(&mut guard, &accounts[0].info(), pools[0], executor, 50_000, 0).unwrap();
+        assert_eq!(
+            record_exposure(&mut guard, &accounts[0].info(), pools[0], executor, 1, 0).unwrap_err(),
+            ErrorCode::PoolExposureExceeded.into()
+        );
+        record_exposure(&mut guard, &accounts[1].info(), pools[1], executor, 50_000, 0).unwrap();
+        record_exposure(&mut guard, &accounts[2].info(), pools[2], executor, 50_000, 0).unwrap();
+        assert_eq!(
+            record_exposure(&mut guard, &accounts[3].info(), pools[3], executor, 1, 0).unwrap_err(),
+            ErrorCode::TotalExposureExceeded.into()
+        );
+
+        assert_eq!(guard.open_exposure, 150_000);
+        assert_eq!(open_exposure(&accounts[0]), 50_000);
+        assert_eq!(open_exposure(&accounts[3]), 0);
+    }
+
+    #[test]
+    fn transfers_to_unregistered_pools_are_not_exposure() {
+        let mut account = TestAccount::guard(&guard());
+        let info = account.info();
+        let mut guard = Account::<GuardState>::try_from(&info).unwrap();
+        let destination = Pubkey::new_unique();
+        let (key, _) = Pubkey::find_program_address(&[b"pool", guard.key().as_ref(), destination.as_ref()], &crate::ID);
+
+        let mut missing = TestAccount::uninitialized(key);
+        record_exposure(&mut guard, &missing.info(), destination, Pubkey::new_unique(), 1_000, 0).unwrap();
+        assert_eq!(guard.open_exposure, 0);
+
+        // The PDA must be the destination owner's, registered or not
+        let mut other = TestAccount::uninitialized(Pubkey::new_unique());
+        assert_eq!(
+            record_exposure(&mut guard, &other.info(), destination, Pubkey::new_unique(), 1_000, 0).unwrap_err(),
+            ErrorCode::BettingPoolAccountMismatch.into()
+        );
+    }
+
+    #[test]
+    fn release_cannot_exceed_open_exposure() {
+        let mut guard = guard();
+        guard.open_exposure = 300;
+        let mut pool = BettingPool {
+            guard: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            open_exposure: 100,
+            total_opened: 100,
+            total_payout: 0,
+            bump: 0,
+        };
+
+        assert_eq!(release_exposure(&mut guard, &mut pool, 60).unwrap(), (40, 240));
+        assert_eq!(
+            release_exposure(&mut guard, &mut pool, 41).unwrap_err(),
+            ErrorCode::ExposureUnderflow.into()
+        );
+        assert_eq!(
+            release_exposure(&mut guard, &mut pool, 0).unwrap_err(),
+            ErrorCode::ZeroExposureAmount.into()
+        );
+        assert_eq!((pool.open_exposure, guard.open_exposure), (40, 240));
+    }
+
+    #[test]
+    fn pool_changes_need_execute_pool_change() {
+        let pool = Pubkey::new_unique();
+        let register = GuardChange::RegisterBettingPool { pool };
+        let release = GuardChange::ReleaseExposure { pool, amount: 1, payout: None };
+
+        // Registering waits out the timelock; a release needs only the quorum
+        assert!(register.requires_timelock());
+        assert!(!release.requires_timelock());
+
+        let mut guard = guard();
+        for change in [register, release] {
+            assert_eq!(apply_change(&mut guard, change, 0).unwrap_err(), ErrorCode::WrongExecuteInstruction.into());
+        }
+        assert_eq!(
+            validate_change(&GuardChange::ReleaseExposure { pool, amount: 0, payout: None }).unwrap_err(),
+            ErrorCode::ZeroExposureAmount.into()
+        );
+    }
+
+    /// A change queued at `queued_at` with the given approval bitmap
+    fn pending(change: GuardChange, queued_at: i64, eta: i64, approvals: u8) -> PendingChange {
+        PendingChange {
+            guard: Pubkey::new_unique(),
+            change_id: 0,
+            change,
+            queued_at,
+            eta,
+            proposer: Pubkey::new_unique(),
+            admin_set_version: 0,
+            approvals,
+            rejections: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn queued_eta_respects_the_timelock() {
+        let mut guard = guard();
+        guard.timelock_seconds = DEFAULT_TIMELOCK_SECONDS;
+        let now = 1_000;
+        let earliest = now + DEFAULT_TIMELOCK_SECONDS as i64;
+        let change = GuardChange::SetDailySpendBps { bps: 50 };
+
+        assert_eq!(queue_eta(&guard, &change, earliest - 1, now).unwrap_err(), ErrorCode::TimelockTooShort.into());
+        assert_eq!(queue_eta(&guard, &change, earliest, now).unwrap(), earliest);
+        assert_eq!(queue_eta(&guard, &change, earliest + 60, now).unwrap(), earliest + 60);
+        // Quorum-only changes execute as soon as they are approved
+        assert_eq!(queue_eta(&guard, &GuardChange::Unpause, 0, now).unwrap(), now);
+    }
+
+    #[test]
+    fn execution_needs_the_quorum() {
+        let mut guard = guard();
+        guard.admin_threshold = 2;
+        let change = GuardChange::Unpause;
+
+        assert_eq!(
+            check_executable(&guard, &pending(change, 0, 0, 0b001), 0).unwrap_err(),
+            ErrorCode::InsufficientApprovals.into()
+        );
+        assert!(check_executable(&guard, &pending(change, 0, 0, 0b101), 0).is_ok());
+    }
+
+    #[test]
+    fn execution_waits_out_the_timelock_until_expiry() {
+        let mut guard = guard();
+        guard.admin_threshold = 1;
+        guard.timelock_seconds = MIN_TIMELOCK_SECONDS;
+        let queued_at = 1_000;
+        let eta = queued_at + DEFAULT_TIMELOCK_SECONDS as i64;
+        let pending = pending(GuardChange::SetCooldownSeconds { seconds: 7_200 }, queued_at, eta, 1);
+
+        assert_eq!(check_executable(&guard, &pending, eta - 1).unwrap_err(), ErrorCode::TimelockNotElapsed.into());
+        assert!(check_executable(&guard, &pending, eta).is_ok());
+        let expiry = eta + CHANGE_GRACE_PERIOD_SECONDS;
+        assert!(check_executable(&guard, &pending, expiry).is_ok());
+        assert_eq!(check_executable(&guard, &pending, expiry + 1).unwrap_err(), ErrorCode::ChangeExpired.into());
+
+        // Raising the timelock after queueing also delays the change
+        guard.timelock_seconds = MAX_TIMELOCK_SECONDS;
+        let unlock = queued_at + MAX_TIMELOCK_SECONDS as i64;
+        assert_eq!(check_executable(&guard, &pending, eta).unwrap_err(), ErrorCode::TimelockNotElapsed.into());
+        assert!(check_executable(&guard, &pending, unlock).is_ok());
+    }
+
+    #[test]
+    fn admin_set_change_invalidates_queued_approvals() {
+        let mut guard = guard();
+        guard.admin_threshold = 1;
+        let mut admins = [Pubkey::default(); MAX_ADMINS];
+        admins[0] = Pubkey::new_unique();
+        let queued = pending(GuardChange::Unpause, 0, 0, 1);
+        assert!(check_executable(&guard, &queued, 0).is_ok());
+
+        apply_change(&mut guard, GuardChange::SetAdmins { admins, threshold: 1 }, 0).unwrap();
+        assert_eq!(check_executable(&guard, &queued, 0).unwrap_err(), ErrorCode::StaleAdminSet.into());
+    }
+
+    #[test]
+    fn cooldown_cannot_be_set_below_the_floor() {
+        assert!(validate_cooldown(DEFAULT_COOLDOWN_SECONDS).is_ok());
+        let too_short = GuardChange::SetCooldownSeconds { seconds: MIN_COOLDOWN_SECONDS - 1 };
+        assert_eq!(validate_change(&too_short).unwrap_err(), ErrorCode::CooldownOutOfBounds.into());
+
+        // Also enforced when the change is applied
+        let mut guard = guard();
+        guard.cooldown_seconds = DEFAULT_COOLDOWN_SECONDS;
+        assert_eq!(apply_change(&mut guard, too_short, 0).unwrap_err(), ErrorCode::CooldownOutOfBounds.into());
+        assert_eq!(guard.cooldown_seconds, DEFAULT_COOLDOWN_SECONDS);
+        apply_change(&mut guard, GuardChange::SetCooldownSeconds { seconds: MIN_COOLDOWN_SECONDS }, 0).unwrap();
+        assert_eq!(guard.cooldown_seconds, MIN_COOLDOWN_SECONDS);
+    }
+
+    #[test]
+    fn repeated_evaluation_in_one_transaction_hits_the_cooldown() {
+        let now = 1_000_000;
+        let mut state = guard();
+        state.cooldown_seconds = MIN_COOLDOWN_SECONDS;
+        state.daily_spend_bps = DEFAULT_DAILY_SPEND_BPS;
+        state.max_drawdown_bps = DEFAULT_MAX_DRAWDOWN_BPS;
+        state.treasury_value = 1_000_000_000;
+        state.treasury_value_updated_ts = now;
+        state.last_reset_ts = now;
+        let mut account = TestAccount::guard(&state);
+        let info = account.info();
+        let mut guard = Account::<GuardState>::try_from(&info).unwrap();
+
+        let evaluate = |guard: &GuardState| {
+            let feeds = rule_feeds(None, None, now).unwrap();
+            let listings = Listings { destination: None, target_program: None };
+            build_verdict(guard, feeds, listings, 1_000, 1, OperationKind::Spend, now).unwrap()
+        };
+        assert!(evaluate(&guard).violations.is_empty());
+        record_approval(&mut guard, 1_000, OperationKind::Spend, now).unwrap();
+
+        // A second CPI evaluation under the same instruction sees the same clock
+        let verdict = evaluate(&guard);
+        let error: anchor_lang::error::Error = verdict.violations.first().unwrap().error().into();
+        assert_eq!(error, ErrorCode::CooldownActive.into());
+    }
 }
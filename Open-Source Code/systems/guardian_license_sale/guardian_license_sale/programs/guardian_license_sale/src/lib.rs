@@ -3,6 +3,8 @@ use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, Tran
 use anchor_spl::associated_token::AssociatedToken;
 use mpl_core::instructions::CreateV1CpiBuilder;
 use mpl_core::ID as METAPLEX_CORE_ID;
+use policy_guard::program::PolicyGuard;
+use policy_guard::cpi::accounts::RecordRevenue;
 
 declare_id!("FfA1sZdQcEps96tMhNKxwu1s3MaLx86wMHCGigKUAtpm");
 
@@ -100,7 +102,19 @@ pub mod guardian_license_sale {
             tier_index,
         )?;
 
-        // 3. Update State
+        // 3. Report revenue (valued as wrapped SOL)
+        record_sale_revenue(
+            &ctx.accounts.sale_state.to_account_info(),
+            bump,
+            ctx.accounts.policy_guard_program.as_ref(),
+            ctx.accounts.guard_state.as_ref(),
+            ctx.accounts.revenue_tracker.as_ref(),
+            ctx.accounts.price_feed.as_ref(),
+            anchor_spl::token::spl_token::native_mint::ID,
+            price,
+        )?;
+
+        // 4. Update State
         let sale_state = &mut ctx.accounts.sale_state;
         let tier = &mut sale_state.tiers[tier_index as usize];
         tier.sold = tier.sold.checked_add(1).ok_or(SaleError::MathOverflow)?;
@@ -149,7 +163,19 @@ pub mod guardian_license_sale {
             tier_index,
         )?;
 
-        // 3. Update State
+        // 3. Report revenue
+        record_sale_revenue(
+            &ctx.accounts.sale_state.to_account_info(),
+            bump,
+            ctx.accounts.policy_guard_program.as_ref(),
+            ctx.accounts.guard_state.as_ref(),
+            ctx.accounts.revenue_tracker.as_ref(),
+            ctx.accounts.price_feed.as_ref(),
+            ctx.accounts.payment_mint.key(),
+            price,
+        )?;
+
+        // 4. Update State
         let sale_state = &mut ctx.accounts.sale_state;
         let tier = &mut sale_state.tiers[tier_index as usize];
         tier.sold = tier.sold.checked_add(1).ok_or(SaleError::MathOverflow)?;
@@ -195,6 +221,44 @@ fn mint_license_nft<'info>(
     Ok(())
 }
 
+/// Record a sale as Policy Guard RULE_03 revenue, signed by the sale_state PDA
+/// (which the DAO registers as a revenue reporter). Skipped when the guard
+/// program is not passed.
+fn record_sale_revenue<'info>(
+    sale_state: &AccountInfo<'info>,
+    bump: u8,
+    guard_program: Option<&Program<'info, PolicyGuard>>,
+    guard_state: Option<&UncheckedAccount<'info>>,
+    revenue_tracker: Option<&UncheckedAccount<'info>>,
+    price_feed: Option<&UncheckedAccount<'info>>,
+    mint: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let Some(guard_program) = guard_program else {
+        return Ok(());
+    };
+    let (Some(guard_state), Some(revenue_tracker), Some(price_feed)) = (guard_state, revenue_tracker, price_feed) else {
+        return Err(SaleError::MissingRevenueAccounts.into());
+    };
+
+    let seeds = &[&b"sale_state"[..], &[bump]];
+    let signer_seeds = &[&seeds[..]];
+    policy_guard::cpi::record_revenue(
+        CpiContext::new_with_signer(
+            guard_program.to_account_info(),
+            RecordRevenue {
+                guard_state: guard_state.to_account_info(),
+                revenue_tracker: revenue_tracker.to_account_info(),
+                price_feed: price_feed.to_account_info(),
+                reporter: sale_state.clone(),
+            },
+            signer_seeds,
+        ),
+        mint,
+        amount,
+    )
+}
+
 #[derive(Accounts)]
 pub struct InitializeSale<'info> {
     #[account(
@@ -267,6 +331,16 @@ pub struct PurchaseLicenseSol<'info> {
     #[account(address = METAPLEX_CORE_ID)]
     pub core_program: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
+
+    /// Policy Guard (optional): records the sale as RULE_03 revenue
+    pub policy_guard_program: Option<Program<'info, PolicyGuard>>,
+    /// CHECK: Guard instance, validated by the Policy Guard
+    pub guard_state: Option<UncheckedAccount<'info>>,
+    /// CHECK: Guard revenue tracker, validated by the Policy Guard
+    #[account(mut)]
+    pub revenue_tracker: Option<UncheckedAccount<'info>>,
+    /// CHECK: Price feed registered for the payment mint, validated by the Policy Guard
+    pub price_feed: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -304,6 +378,16 @@ pub struct PurchaseLicenseUsdc<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    /// Policy Guard (optional): records the sale as RULE_03 revenue
+    pub policy_guard_program: Option<Program<'info, PolicyGuard>>,
+    /// CHECK: Guard instance, validated by the Policy Guard
+    pub guard_state: Option<UncheckedAccount<'info>>,
+    /// CHECK: Guard revenue tracker, validated by the Policy Guard
+    #[account(mut)]
+    pub revenue_tracker: Option<UncheckedAccount<'info>>,
+    /// CHECK: Price feed registered for the payment mint, validated by the Policy Guard
+    pub price_feed: Option<UncheckedAccount<'info>>,
 }
 
 #[account]
@@ -347,4 +431,6 @@ pub enum SaleError {
     Unauthorized,
     #[msg("Math overflow")]
     MathOverflow,
+    #[msg("Revenue reporting needs the guard state, revenue tracker and price feed")]
+    MissingRevenueAccounts,
 }
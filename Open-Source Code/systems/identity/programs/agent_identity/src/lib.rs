@@ -81,6 +81,46 @@ pub mod agent_identity {
         Ok(())
     }
 
+    // Governance: Suspend or Reinstate an Agent (DAO Only)
+    pub fn set_suspended(ctx: Context<UpgradeKYA>, suspended: bool) -> Result<()> {
+        let identity = &mut ctx.accounts.agent_identity;
+        let config = &ctx.accounts.config;
+
+        require!(ctx.accounts.dao_authority.key() == config.admin, ErrorCode::Unauthorized);
+
+        identity.suspended = suspended;
+        msg!("Agent {} {}", identity.name, if suspended { "SUSPENDED" } else { "reinstated" });
+        Ok(())
+    }
+
+    // Migration: Grow identities created before `suspended` to the current size
+    // Only needed for 32-char names; shorter names already have slack. Permissionless.
+    pub fn migrate_identity(ctx: Context<MigrateIdentity>) -> Result<()> {
+        let identity = ctx.accounts.agent_identity.to_account_info();
+        if identity.data_len() >= AgentIdentity::SPACE {
+            return Ok(());
+        }
+
+        let shortfall = Rent::get()?
+            .minimum_balance(AgentIdentity::SPACE)
+            .saturating_sub(identity.lamports());
+        if shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: identity.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        identity.realloc(AgentIdentity::SPACE, true)?;
+        msg!("Agent identity migrated: {}", identity.key());
+        Ok(())
+    }
+
     // ========================================================================
     // SUBSCRIBE: Pay $GRID to Auto-Upgrade KYA Level (Tier 1 or 2)
     // ========================================================================
@@ -220,6 +260,26 @@ pub struct UpgradeKYA<'info> {
     pub agent_identity: Account<'info, AgentIdentity>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateIdentity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any wallet; only used as a seed
+    pub agent_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Legacy identities may not deserialize until grown
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"agent-id", agent_wallet.key().as_ref()],
+        bump
+    )]
+    pub agent_identity: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct SubscribeTier<'info> {
     #[account(mut)]
@@ -273,10 +333,20 @@ pub struct AgentIdentity {
     pub task_count: u64,        // 8
     pub bump: u8,               // 1
     pub subscription_expiry: i64, // 8 (New: Subscription Expiry Timestamp)
+    pub suspended: bool,        // 1 (DAO suspension: Passport status "Suspended")
 }
 
 impl AgentIdentity {
-    pub const SPACE: usize = 8 + 32 + 32 + 36 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 8;
+    pub const SPACE: usize = 8 + 32 + 32 + 36 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 8 + 1;
+
+    /// Deserialize an identity, including ones not yet grown by migrate_identity
+    /// (a legacy account ends right before `suspended`, which reads as false)
+    pub fn load(data: &[u8]) -> Result<AgentIdentity> {
+        let mut padded = [0u8; AgentIdentity::SPACE];
+        let len = data.len().min(AgentIdentity::SPACE);
+        padded[..len].copy_from_slice(&data[..len]);
+        AgentIdentity::try_deserialize(&mut &padded[..])
+    }
 }
 
 // ============================================================================
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, Burn, burn};
+use policy_guard::program::PolicyGuard;
+use policy_guard::cpi::accounts::RecordRevenue;
 
 // ============================================================================
 // PROGRAM ID - UPDATE BEFORE DEPLOYMENT
@@ -45,6 +47,8 @@ pub mod grid_fee_sweep {
     // INSTRUCTION: Sweep Fees
     // Distributes collected fees: 50% to burn, 50% to ops
     // PERMISSIONLESS - anyone can call it
+    // When the Policy Guard accounts are passed, the swept amount is recorded
+    // as RULE_03 revenue, signed by the fee vault PDA (a registered reporter).
     // ========================================================================
     pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
         let amount = ctx.accounts.fee_vault_token_account.amount;
@@ -115,6 +119,32 @@ pub mod grid_fee_sweep {
             msg!("💸 Transferred {} to Ops", ops_amount);
         }
 
+        // 3. REPORT REVENUE TO THE POLICY GUARD
+        if let Some(guard_program) = &ctx.accounts.policy_guard_program {
+            let (Some(guard_state), Some(revenue_tracker), Some(price_feed)) = (
+                &ctx.accounts.guard_state,
+                &ctx.accounts.revenue_tracker,
+                &ctx.accounts.price_feed,
+            ) else {
+                return Err(ErrorCode::MissingRevenueAccounts.into());
+            };
+            policy_guard::cpi::record_revenue(
+                CpiContext::new_with_signer(
+                    guard_program.to_account_info(),
+                    RecordRevenue {
+                        guard_state: guard_state.to_account_info(),
+                        revenue_tracker: revenue_tracker.to_account_info(),
+                        price_feed: price_feed.to_account_info(),
+                        reporter: ctx.accounts.fee_vault_authority.to_account_info(),
+                    },
+                    vault_seeds,
+                ),
+                mint_key,
+                amount,
+            )?;
+            msg!("📈 Recorded {} as revenue", amount);
+        }
+
         msg!("✅ Fee sweep complete");
         Ok(())
     }
@@ -247,6 +277,19 @@ pub struct SweepFees<'info> {
     pub ops_token_account: InterfaceAccount<'info, TokenAccount>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Policy Guard (optional): records the sweep as RULE_03 revenue
+    pub policy_guard_program: Option<Program<'info, PolicyGuard>>,
+
+    /// CHECK: Guard instance, validated by the Policy Guard
+    pub guard_state: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Guard revenue tracker, validated by the Policy Guard
+    #[account(mut)]
+    pub revenue_tracker: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Price feed registered for the mint, validated by the Policy Guard
+    pub price_feed: Option<UncheckedAccount<'info>>,
 }
 
 // ============================================================================
@@ -282,4 +325,6 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Only the Mint Authority can initialize.")]
     UnauthorizedMintAuthority,
+    #[msg("Revenue reporting needs the guard state, revenue tracker and price feed")]
+    MissingRevenueAccounts,
 }
@@ -1,8 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked},
+    token_2022::Token2022,
+    token_2022_extensions::transfer_fee::{
+        harvest_withheld_tokens_to_mint, transfer_fee_set, withdraw_withheld_tokens_from_mint,
+        HarvestWithheldTokensToMint, TransferFeeSetTransferFee, WithdrawWithheldTokensFromMint,
+    },
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
+use spl_token_2022::extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Account as SplTokenAccount;
 use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 use spl_tlv_account_resolution::{
     account::ExtraAccountMeta,
@@ -12,8 +20,9 @@ use spl_tlv_account_resolution::{
 use policy_guard::program::PolicyGuard;
 use policy_guard::cpi::accounts::EvaluateTx as PolicyGuardEvaluate;
 use policy_guard::cpi::evaluate_transaction as policy_guard_evaluate;
-use agent_identity::program::AgentIdentity as AgentIdentityProgram;
-use agent_identity::state::AgentIdentity;
+use policy_guard::{Counterparty, CounterpartyListing, Listing};
+use grid_policy_core::{self as core, PriceFeedData, PriceTolerance, QUOTE_DECIMALS};
+use agent_identity::AgentIdentity;
 
 declare_id!("7Py52EPwuCxYJ7UiBKrk5ce14T4NTxuutHFtyoWDdqFV");
 
@@ -24,9 +33,29 @@ pub const OPS_WALLET: Pubkey = anchor_lang::solana_program::pubkey!("BqPoJnqNLeQ
 #[cfg(not(feature = "mainnet"))]
 pub const OPS_WALLET: Pubkey = anchor_lang::solana_program::pubkey!("BqPoJnqNLeQZCV5d9YY3Fo2LwFw17fRZbTTkEWGJJRUU");
 
-// Bootstrap Admin for initializing Config (Matches previous DAO_KEY)
+// Bootstrap Admin for initializing Config of mints without a mint authority (Matches previous DAO_KEY)
 pub const BOOTSTRAP_ADMIN: Pubkey = anchor_lang::solana_program::pubkey!("BqPoJnqNLeQZCV5d9YY3Fo2LwFw17fRZbTTkEWGJJRUU");
 
+// grid_fee_sweep program; harvested fees go to its [b"fee-vault", mint] vault,
+// which splits them 50/50 into burn and ops
+pub const FEE_SWEEP_PROGRAM: Pubkey = anchor_lang::solana_program::pubkey!("EnLPB3HoiWku9PfMKxD8RUkmwdvoTPWLZY82nGD2Bwuz");
+
+// Token-2022 TransferFee rate: 2% (1% burn, 1% ops), DAO-adjustable 2% - 3%
+// The sweep burns half, and the 1% burn is permanent (CONSTITUTION), so 2% is the floor
+pub const DEFAULT_TRANSFER_FEE_BPS: u16 = 200;
+pub const MIN_TRANSFER_FEE_BPS: u16 = 200;
+pub const MAX_TRANSFER_FEE_BPS: u16 = 300;
+
+// Upper bound for the staleness tolerance of the tier and guard price feeds
+pub const MAX_TIER_PRICE_STALENESS_SECONDS: u32 = 60 * 60;
+
+// Number of extra accounts the hook resolves (see extra_account_metas)
+pub const EXTRA_ACCOUNT_METAS: usize = 18;
+
+// Velocity windows are tracked in VELOCITY_BUCKETS sub-buckets, up to 7 days
+pub const VELOCITY_BUCKETS: usize = 6;
+pub const MAX_VELOCITY_WINDOW_SECONDS: u32 = 7 * 24 * 60 * 60;
+
 #[program]
 pub mod grid_transfer_hook {
     use super::*;
@@ -37,36 +66,7 @@ pub mod grid_transfer_hook {
     pub fn initialize_extra_account_meta_list(
         ctx: Context<InitializeExtraAccountMetaList>,
     ) -> Result<()> {
-        // Define extra accounts required for the transfer hook
-        // 1. Ops Token Account (to receive 1% tax)
-        // 2. Burn Token Account (to receive 1% burn)
-        // 3. Blacklist Account (for firewall)
-        
-        let account_metas = vec![
-            // Account 6: Blacklist (Extra Account Meta Index 0)
-            ExtraAccountMeta::new_with_seeds(
-                &[Seed::Literal { bytes: b"firewall-blacklist".to_vec() }],
-                false, // is_signer
-                false, // is_writable
-            )?,
-            // Account 7: Policy Guard State (Extra Account Meta Index 1)
-            ExtraAccountMeta::new_with_seeds(
-                &[Seed::Literal { bytes: b"guard".to_vec() }],
-                false, // is_signer
-                false, // is_writable
-            )?,
-            // Account 8: Policy Guard Program (Extra Account Meta Index 2)
-            ExtraAccountMeta::new_with_pubkey(
-                &anchor_lang::solana_program::pubkey!("2u4LtXMzdttMEV54jFEWnQrvCbx3TguyvWyfdho7MFRW"),
-                false, // is_signer
-                false, // is_writable
-            )?,
-        ];
-
-        // Note: For full Tax implementation, we'd need to pass account metas for Ops/Burn.
-        // Keeping it minimal for Logic Checks per Audit requirements.
-
-        let account_size = ExtraAccountMetaList::size_of(account_metas.len())? as u64;
+        let account_size = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS)? as u64;
         let lamports = Rent::get()?.minimum_balance(account_size as usize);
 
         let mint = ctx.accounts.mint.key();
@@ -92,7 +92,7 @@ pub mod grid_transfer_hook {
 
         ExtraAccountMetaList::init::<ExecuteInstruction>(
             &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
-            &account_metas,
+            &extra_account_metas(&ctx.accounts.config)?,
         )?;
 
         msg!("ExtraAccountMetaList initialized for mint: {}", mint);
@@ -100,24 +100,93 @@ pub mod grid_transfer_hook {
     }
 
 
+    // ========================================================================
+    // INSTRUCTION: Update Extra Account Meta List
+    // ========================================================================
+    /// Rewrite the list from the current config (e.g. after update_config),
+    /// resizing it if the layout grew. Mint authority or DAO.
+    pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            ctx.accounts.mint.mint_authority.contains(&authority) || ctx.accounts.config.admin == authority,
+            ErrorCode::Unauthorized
+        );
+
+        let list = ctx.accounts.extra_account_meta_list.to_account_info();
+        let account_size = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_METAS)?;
+        if list.data_len() != account_size {
+            let shortfall = Rent::get()?.minimum_balance(account_size).saturating_sub(list.lamports());
+            if shortfall > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: list.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            list.realloc(account_size, false)?;
+        }
+
+        ExtraAccountMetaList::update::<ExecuteInstruction>(
+            &mut list.try_borrow_mut_data()?,
+            &extra_account_metas(&ctx.accounts.config)?,
+        )?;
+
+        msg!("ExtraAccountMetaList rewritten for mint: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
     // ========================================================================
     // INSTRUCTION: Initialize Config (Bootstrap)
     // ========================================================================
-    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        agent_id_program: Pubkey,
+        policy_guard_program: Pubkey,
+    ) -> Result<()> {
+        // Each mint gets its own config, created by its mint authority
+        // (the bootstrap admin stands in for mints with a revoked authority)
+        let admin = ctx.accounts.admin.key();
+        require!(
+            ctx.accounts.mint.mint_authority.map_or(admin == BOOTSTRAP_ADMIN, |authority| authority == admin),
+            ErrorCode::Unauthorized
+        );
+
         let config = &mut ctx.accounts.config;
-        config.admin = ctx.accounts.admin.key();
-        msg!("Transfer Hook Config Initialized. Admin: {}", config.admin);
+        config.admin = admin;
+        config.mint = ctx.accounts.mint.key();
+        config.agent_id_program = agent_id_program;
+        config.policy_guard_program = policy_guard_program;
+        msg!("Transfer Hook Config Initialized for mint {}. Admin: {}", config.mint, config.admin);
         Ok(())
     }
 
     // ========================================================================
-    // INSTRUCTION: Update Admin
+    // INSTRUCTION: Update Config
     // ========================================================================
-    pub fn update_admin(ctx: Context<UpdateAdmin>, new_admin: Pubkey) -> Result<()> {
+    /// Changing a program id leaves the ExtraAccountMetaList stale until
+    /// update_extra_account_meta_list rewrites it.
+    pub fn update_config(
+        ctx: Context<UpdateAdmin>,
+        new_admin: Option<Pubkey>,
+        new_agent_id_program: Option<Pubkey>,
+        new_policy_guard_program: Option<Pubkey>,
+    ) -> Result<()> {
         let config = &mut ctx.accounts.config;
-        let old_admin = config.admin;
-        config.admin = new_admin;
-        msg!("Admin Updated: {} -> {}", old_admin, new_admin);
+        if let Some(admin) = new_admin {
+            msg!("Admin Updated: {} -> {}", config.admin, admin);
+            config.admin = admin;
+        }
+        if let Some(pid) = new_agent_id_program {
+            config.agent_id_program = pid;
+        }
+        if let Some(pid) = new_policy_guard_program {
+            config.policy_guard_program = pid;
+        }
         Ok(())
     }
 
@@ -125,72 +194,183 @@ pub mod grid_transfer_hook {
     // INSTRUCTION: Transfer Hook (Firewall + Tax Logic)
     // ========================================================================
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        // `owner` is not a signer: without this anyone could call Execute
+        // directly and fill a victim's velocity window, slot volume or guard
+        // accumulators. Reject before any state is written.
+        assert_is_transferring(&ctx.accounts.source_token.to_account_info())?;
+
         let source_owner = ctx.accounts.owner.key();
         let dest_owner = ctx.accounts.destination_token.owner;
         
         // 1. FIREWALL CHECKS
         // ------------------
-        if let Some(blacklist) = &ctx.accounts.blacklist {
-            if is_blacklisted(&blacklist.addresses, &source_owner) {
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(entry) = &ctx.accounts.source_blocked {
+            if is_blocked(entry, now)? {
                 msg!("Grid Firewall: BLOCKED - Source {} is blacklisted", source_owner);
                 return Err(ErrorCode::BlacklistedAddress.into());
             }
-            if is_blacklisted(&blacklist.addresses, &dest_owner) {
+        }
+        if let Some(entry) = &ctx.accounts.destination_blocked {
+            if is_blocked(entry, now)? {
                 msg!("Grid Firewall: BLOCKED - Destination {} is blacklisted", dest_owner);
                 return Err(ErrorCode::BlacklistedAddress.into());
             }
         }
 
+        // The list bakes in program ids and the tier feed; a stale list
+        // (config changed since it was written) must be rewritten first
+        let config = ctx.accounts.config.as_ref().ok_or(ErrorCode::StaleExtraAccountMetaList)?;
+        require!(
+            ctx.accounts.policy_guard_program.as_ref().map(|p| p.key()) == Some(config.policy_guard_program)
+                && ctx.accounts.agent_identity_program.key() == config.agent_id_program
+                && ctx.accounts.tier_price_feed.as_ref().map(|a| a.key()) == Some(config.transfer_tiers.price_feed)
+                && ctx.accounts.holder_activity.is_some()
+                && ctx.accounts.guard_price_feed.as_ref().map(|a| a.key()) == Some(config.guard.price_feed)
+                && (!config.guard.enabled
+                    || ctx.accounts.policy_guard_state.as_ref().is_some_and(|a| a.is_writable)),
+            ErrorCode::StaleExtraAccountMetaList
+        );
+
+        // Exempt senders can skip individual checks; an exempt destination
+        // only gets receive-side relief (the blacklist above always applies)
+        let exemption = read_exemptions(&[
+            ctx.accounts.source_token_exemption.as_deref(),
+            ctx.accounts.source_owner_exemption.as_deref(),
+        ])?;
+        let skip_receive_rules = exemption.skip_kya
+            || read_exemptions(&[
+                ctx.accounts.destination_token_exemption.as_deref(),
+                ctx.accounts.destination_owner_exemption.as_deref(),
+            ])?
+            .skip_receive_rules;
+
+        // Default to Basic for wallets without an identity
+        let source_identity = ctx.accounts.agent_identity
+            .as_ref()
+            .map(|account| read_agent_identity(account, &config.agent_id_program))
+            .transpose()?
+            .flatten();
+        let source_standing = agent_standing(source_identity.as_ref(), &source_owner, now);
+        let kya_level = source_standing.map_or(1, |standing| standing.level);
+
+        // 1b. HOOK MODE (Kill Switch)
+        // ---------------------------
+        // Only senders flagged bypass_mode keep moving; an exempt counterparty
+        // (e.g. an AMM pool) does not reopen a frozen market
+        match config.mode {
+            HookMode::Normal => {}
+            _ if exemption.bypass_mode => {}
+            HookMode::AgentsOnly => {
+                if !source_standing.is_some_and(|standing| standing.passport) {
+                    msg!("Grid Firewall: BLOCKED - AgentsOnly mode, {} has no active passport", source_owner);
+                    return Err(ErrorCode::PassportRequired.into());
+                }
+            }
+            HookMode::AllowlistOnly => {
+                let allowed = match &ctx.accounts.destination_listing {
+                    Some(listing) => is_allowlisted(listing, config, &dest_owner)?,
+                    None => false,
+                };
+                if !allowed {
+                    msg!("Grid Firewall: BLOCKED - AllowlistOnly mode, {} is not allowlisted", dest_owner);
+                    return Err(ErrorCode::DestinationNotAllowlisted.into());
+                }
+            }
+            HookMode::Frozen => {
+                msg!("Grid Firewall: BLOCKED - Mint {} is FROZEN", config.mint);
+                return Err(ErrorCode::HookFrozen.into());
+            }
+        }
+
         // 2. POLICY GUARD CPI (AI Firewall)
         // ---------------------------------
-        if let (Some(guard_state), Some(guard_program)) = (
-            ctx.accounts.policy_guard_state.as_ref(),
-            ctx.accounts.policy_guard_program.as_ref(),
-        ) {
+        // Opt-in per mint: every guarded transfer is an evaluation
+        if !config.guard.enabled {
+            msg!("Policy Guard: NOT ENABLED for this mint - Skipping limit checks");
+        } else if exemption.skip_guard {
+            msg!("Policy Guard: EXEMPT - Skipping limit checks");
+        } else {
+            let (Some(guard_state), Some(guard_program), Some(instructions_sysvar), Some(feed)) = (
+                ctx.accounts.policy_guard_state.as_ref(),
+                ctx.accounts.policy_guard_program.as_ref(),
+                ctx.accounts.instructions_sysvar.as_ref(),
+                ctx.accounts.guard_price_feed.as_ref(),
+            ) else {
+                return Err(ErrorCode::StaleExtraAccountMetaList.into());
+            };
+
+            // The guard's limits are in quote units (USD, 6 decimals)
+            let tolerance = config.guard.price_tolerance();
+            let value = usd_value(feed, &tolerance, amount, ctx.accounts.mint.decimals, QUOTE_DECIMALS, now)?;
+
             let cpi_accounts = PolicyGuardEvaluate {
                 guard_state: guard_state.to_account_info(),
+                revenue_tracker: None,
+                decision_log: None,
+                destination_listing: ctx.accounts.destination_listing.as_ref().map(|a| a.to_account_info()),
+                program_listing: None,
+                instructions_sysvar: instructions_sysvar.to_account_info(),
+                authority: None,
             };
             let cpi_ctx = CpiContext::new(guard_program.to_account_info(), cpi_accounts);
-            
-            // DYNAMIC KYA LOOKUP
-            // Default to Basic (1) for humans/unregistered to allow network usage.
-            // Agents will have their elevated limits respected.
-            let mut kya_level: u8 = 1; 
-
-            if let Some(agent_id_account) = &ctx.accounts.agent_identity {
-                // Verify the account is owned by the expected program and matches the source owner
-                if agent_id_account.agent_wallet == ctx.accounts.owner.key() { 
-                    // Manual check: Agent Identity must belong to the source wallet
-                    
-                    // CHECK EXPIRY
-                    let clock = Clock::get()?;
-                    if agent_id_account.subscription_expiry > clock.unix_timestamp {
-                        kya_level = agent_id_account.kya_level;
-                        msg!("Transfer Hook: Authenticated Agent (Level {})", kya_level);
-                    } else {
-                        // Expired - downgrade to Basic
-                        kya_level = 1; 
-                        msg!("Transfer Hook: Agent Subscription EXPIRED. Treated as Level 1.");
-                    }
-                }
-            }
+            // Older meta lists predate the listing account; skip the counterparty check
+            let counterparty = Counterparty {
+                destination_owner: ctx.accounts.destination_listing.is_some().then_some(dest_owner),
+                target_program: None,
+            };
 
-            policy_guard_evaluate(cpi_ctx, amount, kya_level)?;
-            msg!("Policy Guard: APPROVED transfer of {} tokens (KYA {})", amount, kya_level);
-        } else {
-            msg!("Policy Guard: NOT CONFIGURED - Skipping limit checks");
+            policy_guard_evaluate(cpi_ctx, value, kya_level, source_owner, counterparty)?;
+            msg!("Policy Guard: APPROVED transfer of {} tokens (value {}, KYA {})", amount, value, kya_level);
         }
         
-        // 2. ANTI-SNIPE & SUSPICIOUS ACTIVITY MONITOR
-        // -------------------------------------------
-        // Threshold: 10% of Total Supply (approx 100M tokens)
-        const SUPPLY: u64 = 1_073_741_824 * 1_000_000_000; 
-        let threshold = SUPPLY / 10;
-        
-        // [ANTI-SNIPE] Block large transfers immediately after launch
-        if amount > threshold {
-            msg!("Grid Firewall: ALERT - Large transfer {} (>{} threshold). ANTI-SNIPE ACTIVE.", amount, threshold);
-            return Err(ErrorCode::AntiSnipeTriggered.into()); // ACTION: Block
+        // 2b. KYA RULES
+        // -------------
+        // Value tiers: the source's KYA level caps a single transfer
+        let tiers = &config.transfer_tiers;
+        if !exemption.skip_kya && tiers.is_enabled() {
+            let value = match tiers.unit {
+                TierUnit::Tokens => amount,
+                TierUnit::Usd => {
+                    let feed = ctx.accounts.tier_price_feed.as_ref().ok_or(ErrorCode::StaleExtraAccountMetaList)?;
+                    usd_value(feed, &tiers.price_tolerance(), amount, ctx.accounts.mint.decimals, 0, now)?
+                }
+            };
+            enforce_transfer_tier(tiers, kya_level, value)?;
+        }
+
+        // Receive side: wallets without an identity are Tier 0 (KYA_SPEC: no $GRID earnings)
+        if !skip_receive_rules {
+            let identity = ctx.accounts.destination_identity
+                .as_ref()
+                .map(|account| read_agent_identity(account, &config.agent_id_program))
+                .transpose()?
+                .flatten();
+            let standing = agent_standing(identity.as_ref(), &dest_owner, now);
+            enforce_receive_rules(&config.receive_rules, standing, &dest_owner, amount)?;
+        }
+
+        // 3. ANTI-SNIPE PROTECTION
+        // ------------------------
+        // Launch window only, per the mint's LaunchProtection schedule
+        if let (false, Some(protection)) = (exemption.skip_anti_snipe, &ctx.accounts.launch_protection) {
+            enforce_launch_protection(
+                protection,
+                amount,
+                &source_owner,
+                &dest_owner,
+                ctx.accounts.mint.supply,
+                ctx.accounts.destination_token.amount,
+            )?;
+        }
+
+        // 4. VELOCITY LIMITS
+        // ------------------
+        // Rolling per-holder limits; the HolderActivity PDA must exist once enabled
+        if let (false, Some(activity)) = (exemption.skip_velocity, &ctx.accounts.holder_activity) {
+            if config.velocity_limits.is_enabled() {
+                enforce_velocity_limits(activity, &config.velocity_limits, &source_owner, amount, now)?;
+            }
         }
         
         // 3. NATIVE TOKEN-2022 TRANSFER FEE (Informational)
@@ -206,39 +386,306 @@ pub mod grid_transfer_hook {
     }
     
     // ========================================================================
-    // INSTRUCTION: Initialize Blacklist (DAO Only)
+    // INSTRUCTION: Hook Mode (Kill Switch)
     // ========================================================================
-    pub fn initialize_blacklist(
-        ctx: Context<InitializeBlacklist>,
-    ) -> Result<()> {
-        let blacklist = &mut ctx.accounts.blacklist;
-        
-        // Verify admin against config
+    /// The guardian can only tighten the mode; loosening it needs the DAO admin
+    pub fn set_hook_mode(ctx: Context<SetHookMode>, mode: HookMode) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let authority = ctx.accounts.authority.key();
+        check_mode_change(config, &authority, mode)?;
+
+        msg!("Hook mode for mint {}: {:?} -> {:?} (by {})", config.mint, config.mode, mode, authority);
+        config.mode = mode;
+        Ok(())
+    }
+
+    pub fn set_hook_guardian(ctx: Context<UpdateAdmin>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.config.guardian = guardian;
+        msg!("Hook guardian set to {}", guardian);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Receive Rules (DAO Only)
+    // ========================================================================
+    pub fn set_receive_rules(ctx: Context<UpdateAdmin>, rules: ReceiveRules) -> Result<()> {
+        require!(rules.min_kya_level <= 3, ErrorCode::InvalidReceiveRules);
+        ctx.accounts.config.receive_rules = rules;
+        msg!("Receive rules updated: {:?}", rules);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Transfer Tiers (DAO Only)
+    // ========================================================================
+    pub fn set_transfer_tiers(ctx: Context<UpdateAdmin>, tiers: TransferTiers) -> Result<()> {
+        // A higher tier may never be capped below a lower one
+        let mut previous: Option<u64> = Some(0);
+        for max in tiers.max_transfer {
+            let ordered = match (previous, max) {
+                (None, Some(_)) => false,
+                (Some(low), Some(high)) => high >= low,
+                (_, None) => true,
+            };
+            require!(ordered, ErrorCode::InvalidTransferTiers);
+            previous = max;
+        }
+        if tiers.unit == TierUnit::Usd {
+            require!(tiers.price_feed != Pubkey::default(), ErrorCode::InvalidTransferTiers);
+            require!(
+                tiers.max_staleness_seconds > 0
+                    && tiers.max_staleness_seconds <= MAX_TIER_PRICE_STALENESS_SECONDS
+                    && tiers.max_confidence_bps as u64 <= BPS_DENOMINATOR,
+                ErrorCode::InvalidTransferTiers
+            );
+        }
+
+        let config = &mut ctx.accounts.config;
+        if tiers.price_feed != config.transfer_tiers.price_feed {
+            msg!("Tier price feed changed; call update_extra_account_meta_list");
+        }
+        config.transfer_tiers = tiers;
+        msg!("Transfer tiers updated: {:?}", tiers);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Policy Guard Settings (DAO Only)
+    // ========================================================================
+    /// Enabling or disabling the guard, or changing its feed, leaves the
+    /// ExtraAccountMetaList stale until update_extra_account_meta_list rewrites it.
+    pub fn set_guard_settings(ctx: Context<UpdateAdmin>, settings: GuardSettings) -> Result<()> {
+        if settings.enabled {
+            require!(
+                settings.price_feed != Pubkey::default()
+                    && settings.max_staleness_seconds > 0
+                    && settings.max_staleness_seconds <= MAX_TIER_PRICE_STALENESS_SECONDS
+                    && settings.max_confidence_bps as u64 <= BPS_DENOMINATOR,
+                ErrorCode::InvalidGuardSettings
+            );
+        }
+
+        let config = &mut ctx.accounts.config;
+        if settings.enabled != config.guard.enabled || settings.price_feed != config.guard.price_feed {
+            msg!("Policy Guard accounts changed; call update_extra_account_meta_list");
+        }
+        config.guard = settings;
+        msg!("Policy Guard settings updated: {:?}", settings);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Velocity Limits
+    // ========================================================================
+    pub fn set_velocity_limits(ctx: Context<UpdateAdmin>, limits: VelocityLimits) -> Result<()> {
         require!(
-            ctx.accounts.admin.key() == ctx.accounts.config.admin,
-            ErrorCode::Unauthorized
+            !limits.is_enabled()
+                || (limits.window_seconds >= VELOCITY_BUCKETS as u32
+                    && limits.window_seconds <= MAX_VELOCITY_WINDOW_SECONDS),
+            ErrorCode::InvalidVelocityLimits
         );
+        ctx.accounts.config.velocity_limits = limits;
+        msg!("Velocity limits updated: {:?}", limits);
+        Ok(())
+    }
+
+    /// Create a holder's activity PDA (permissionless, payer funds rent)
+    /// Wallets need one before sending once velocity limits are enabled.
+    pub fn init_holder_activity(ctx: Context<InitHolderActivity>) -> Result<()> {
+        let activity = &mut ctx.accounts.holder_activity;
+        activity.mint = ctx.accounts.mint.key();
+        activity.owner = ctx.accounts.owner.key();
+        activity.bump = ctx.bumps.holder_activity;
+        msg!("Holder activity initialized for {}", activity.owner);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Exemptions (DAO Only)
+    // ========================================================================
+    /// Exempt an owner or token account from individual hook checks
+    pub fn set_exemption(ctx: Context<SetExemption>, key: Pubkey, flags: ExemptionFlags) -> Result<()> {
+        let exemption = &mut ctx.accounts.exemption;
+        exemption.mint = ctx.accounts.config.mint;
+        exemption.key = key;
+        exemption.flags = flags;
+        exemption.bump = ctx.bumps.exemption;
+        msg!("Firewall: Exemption for {}: {:?}", key, flags);
+        Ok(())
+    }
+
+    pub fn remove_exemption(ctx: Context<RemoveExemption>) -> Result<()> {
+        msg!("Firewall: Exemption removed for {}", ctx.accounts.exemption.key);
+        Ok(())
+    }
 
-        blacklist.count = 0;
-        blacklist.bump = ctx.bumps.blacklist;
-        msg!("Firewall: Blacklist initialized");
+    // ========================================================================
+    // INSTRUCTION: Launch Protection (DAO Only)
+    // ========================================================================
+    pub fn initialize_launch_protection(
+        ctx: Context<InitializeLaunchProtection>,
+        params: LaunchProtectionParams,
+    ) -> Result<()> {
+        let protection = &mut ctx.accounts.launch_protection;
+        protection.mint = ctx.accounts.mint.key();
+        protection.bump = ctx.bumps.launch_protection;
+        protection.apply(params)?;
+        msg!(
+            "Launch protection set for mint {}: launch {}, window {}s",
+            protection.mint,
+            params.launch_ts,
+            params.window_seconds
+        );
         Ok(())
     }
 
-    pub fn add_to_blacklist(
-        ctx: Context<ManageBlacklist>,
-        address: Pubkey,
+    pub fn update_launch_protection(
+        ctx: Context<UpdateLaunchProtection>,
+        params: LaunchProtectionParams,
     ) -> Result<()> {
-        // Verify admin against config
-        require!(
-            ctx.accounts.admin.key() == ctx.accounts.config.admin,
-            ErrorCode::Unauthorized
+        let protection = &mut ctx.accounts.launch_protection;
+        protection.apply(params)?;
+        msg!(
+            "Launch protection updated for mint {}: launch {}, window {}s",
+            protection.mint,
+            params.launch_ts,
+            params.window_seconds
+        );
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Set Transfer Fee (DAO Only)
+    // ========================================================================
+    /// Update the mint's Token-2022 TransferFee rate within the bounds above
+    /// The mint's fee config authority must be the [b"fee-authority", mint]
+    /// PDA. Token-2022 applies the new rate two epochs later.
+    pub fn set_transfer_fee(ctx: Context<SetTransferFee>, fee_bps: u16, maximum_fee: u64) -> Result<()> {
+        validate_transfer_fee(fee_bps, maximum_fee)?;
+
+        let mint = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"fee-authority", mint.as_ref(), &[ctx.bumps.fee_authority]]];
+        transfer_fee_set(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferFeeSetTransferFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.fee_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fee_bps,
+            maximum_fee,
+        )?;
+
+        msg!("Transfer fee set to {} bps (max {}) for mint {}", fee_bps, maximum_fee, mint);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Harvest Fees (Permissionless)
+    // ========================================================================
+    /// Harvest withheld fees from the token accounts in `remaining_accounts`
+    /// into the mint, then withdraw everything withheld on the mint into the
+    /// fee sweep vault, where sweep_fees burns and distributes it.
+    pub fn harvest_fees<'info>(ctx: Context<'_, '_, 'info, 'info, HarvestFees<'info>>) -> Result<()> {
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.mint.key();
+
+        if !ctx.remaining_accounts.is_empty() {
+            harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    token_program.clone(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: token_program.clone(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                ),
+                ctx.remaining_accounts.to_vec(),
+            )?;
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"fee-authority", mint.as_ref(), &[ctx.bumps.fee_authority]]];
+        withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            token_program.clone(),
+            WithdrawWithheldTokensFromMint {
+                token_program_id: token_program,
+                mint: ctx.accounts.mint.to_account_info(),
+                destination: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.fee_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        msg!(
+            "Fees harvested from {} accounts into fee vault {}",
+            ctx.remaining_accounts.len(),
+            ctx.accounts.fee_vault.key()
         );
-        let blacklist = &mut ctx.accounts.blacklist;
-        require!(blacklist.count < 100, ErrorCode::BlacklistFull);
-        blacklist.addresses[blacklist.count as usize] = address;
-        blacklist.count += 1;
-        msg!("Firewall: Added {} to blacklist", address);
+        Ok(())
+    }
+
+    // ========================================================================
+    // INSTRUCTION: Block Addresses (DAO Only)
+    // ========================================================================
+    /// Create or update one BlockedAddress PDA per entry
+    /// `remaining_accounts` must hold the `[b"blocked", mint, address]` PDA of each
+    /// entry, in order. Batches keep large sanctions lists cheap to load.
+    pub fn block_addresses<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageBlocklist<'info>>,
+        entries: Vec<BlockEntry>,
+    ) -> Result<()> {
+        require!(entries.len() <= MAX_BLOCK_BATCH, ErrorCode::BatchTooLarge);
+        require!(ctx.remaining_accounts.len() == entries.len(), ErrorCode::BlockedAccountMismatch);
+        let now = Clock::get()?.unix_timestamp;
+        let admin = ctx.accounts.admin.to_account_info();
+        let mint = ctx.accounts.config.mint;
+
+        for (entry, account) in entries.iter().zip(ctx.remaining_accounts) {
+            let (expected, bump) =
+                Pubkey::find_program_address(&[b"blocked", mint.as_ref(), entry.address.as_ref()], ctx.program_id);
+            require_keys_eq!(account.key(), expected, ErrorCode::BlockedAccountMismatch);
+            if let Some(expiry) = entry.expires_at {
+                require!(expiry > now, ErrorCode::InvalidBlockExpiry);
+            }
+
+            if account.owner != ctx.program_id {
+                create_blocked_account(
+                    &admin,
+                    account,
+                    &ctx.accounts.system_program,
+                    &[b"blocked", mint.as_ref(), entry.address.as_ref(), &[bump]],
+                )?;
+            }
+            let blocked = BlockedAddress {
+                mint,
+                address: entry.address,
+                reason: entry.reason,
+                blocked_at: now,
+                expires_at: entry.expires_at,
+                bump,
+            };
+            blocked.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+            msg!("Firewall: Blocked {} ({:?})", entry.address, entry.reason);
+        }
+        Ok(())
+    }
+
+    /// Close the BlockedAddress PDAs in `remaining_accounts`, refunding the admin
+    pub fn unblock_addresses<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageBlocklist<'info>>,
+    ) -> Result<()> {
+        require!(ctx.remaining_accounts.len() <= MAX_BLOCK_BATCH, ErrorCode::BatchTooLarge);
+        let admin = ctx.accounts.admin.to_account_info();
+
+        for account in ctx.remaining_accounts {
+            let blocked: Account<'info, BlockedAddress> = Account::try_from(account)?;
+            require_keys_eq!(blocked.mint, ctx.accounts.config.mint, ErrorCode::BlockedAccountMismatch);
+            let address = blocked.address;
+            blocked.close(admin.clone())?;
+            msg!("Firewall: Unblocked {}", address);
+        }
         Ok(())
     }
 }
@@ -260,12 +707,41 @@ pub struct InitializeExtraAccountMetaList<'info> {
     )]
     pub extra_account_meta_list: UncheckedAccount<'info>,
 
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, TransferHookConfig>,
+
     pub mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: ExtraAccountMetaList Account, rewritten in place
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, TransferHookConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
     #[account(token::mint = mint)]
@@ -288,37 +764,89 @@ pub struct TransferHook<'info> {
 
     pub token_program: Interface<'info, TokenInterface>, // Account 5
     
-    /// CHECK: Optional blacklist (Account 6)
-    pub blacklist: Option<Account<'info, Blacklist>>,
-
-    /// CHECK: Policy Guard State PDA (Account 7 - Optional)
-    pub policy_guard_state: Option<UncheckedAccount<'info>>,
+    /// CHECK: BlockedAddress PDA of the source owner (Account 6), read by is_blocked
+    #[account(seeds = [b"blocked", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_blocked: Option<UncheckedAccount<'info>>,
 
-    /// Policy Guard Program (Account 8 - Optional)
+    /// Policy Guard Program (Account 7 - Optional)
     pub policy_guard_program: Option<Program<'info, PolicyGuard>>,
 
-    /// Optional Agent Identity (Account 9)
-    /// Used for dynamic KYA limit checks.
+    /// CHECK: Guard instance for this mint (Account 8 - Optional), validated by the Policy Guard
+    /// Writable only while config.guard is enabled.
+    pub policy_guard_state: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Agent Identity PDA of the source owner (Account 9), may be uninitialized
     #[account(
         seeds = [b"agent-id", owner.key().as_ref()], 
         bump,
         seeds::program = agent_identity_program.key()
     )]
-    pub agent_identity: Option<Account<'info, AgentIdentity>>,
+    pub agent_identity: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Agent Identity Program (Account 10), checked against config.agent_id_program
+    pub agent_identity_program: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions Sysvar (Account 11), forwarded to the Policy Guard
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Policy Guard listing of the destination owner (Account 12), validated by the Policy Guard
+    pub destination_listing: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: BlockedAddress PDA of the destination owner (Account 13), read by is_blocked
+    #[account(seeds = [b"blocked", mint.key().as_ref(), destination_token.owner.as_ref()], bump)]
+    pub destination_blocked: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: LaunchProtection PDA of the mint (Account 14), read by enforce_launch_protection
+    #[account(mut, seeds = [b"launch-protection", mint.key().as_ref()], bump)]
+    pub launch_protection: Option<UncheckedAccount<'info>>,
 
-    pub agent_identity_program: Option<Program<'info, AgentIdentityProgram>>,
+    /// CHECK: Exemption PDA of the source token account (Account 15), read by read_exemption
+    #[account(seeds = [b"exempt", mint.key().as_ref(), source_token.key().as_ref()], bump)]
+    pub source_token_exemption: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Exemption PDA of the source owner (Account 16)
+    #[account(seeds = [b"exempt", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_owner_exemption: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Exemption PDA of the destination token account (Account 17)
+    #[account(seeds = [b"exempt", mint.key().as_ref(), destination_token.key().as_ref()], bump)]
+    pub destination_token_exemption: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Exemption PDA of the destination owner (Account 18)
+    #[account(seeds = [b"exempt", mint.key().as_ref(), destination_token.owner.as_ref()], bump)]
+    pub destination_owner_exemption: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Agent Identity PDA of the destination owner (Account 19), may be uninitialized
+    #[account(seeds = [b"agent-id", destination_token.owner.as_ref()], bump, seeds::program = agent_identity_program.key())]
+    pub destination_identity: Option<UncheckedAccount<'info>>,
+
+    /// Hook config holding the receive rules (Account 20)
+    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Option<Account<'info, TransferHookConfig>>,
+
+    /// CHECK: Pyth-layout price feed (Account 21), checked against config.transfer_tiers
+    pub tier_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: HolderActivity PDA of the source owner (Account 22), read by enforce_velocity_limits
+    #[account(mut, seeds = [b"activity", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub holder_activity: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth-layout price feed (Account 23), checked against config.guard
+    pub guard_price_feed: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
-    #[account(mut, address = BOOTSTRAP_ADMIN)]
+    #[account(mut)]
     pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         init,
         payer = admin,
         space = 8 + TransferHookConfig::INIT_SPACE,
-        seeds = [b"config"],
+        seeds = [b"config", mint.key().as_ref()],
         bump
     )]
     pub config: Account<'info, TransferHookConfig>,
@@ -333,7 +861,7 @@ pub struct UpdateAdmin<'info> {
     
     #[account(
         mut,
-        seeds = [b"config"],
+        seeds = [b"config", config.mint.as_ref()],
         bump,
         has_one = admin
     )]
@@ -341,89 +869,1465 @@ pub struct UpdateAdmin<'info> {
 }
 
 #[derive(Accounts)]
-pub struct InitializeBlacklist<'info> {
+pub struct SetHookMode<'info> {
+    /// DAO admin or guardian
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"config", config.mint.as_ref()], bump)]
+    pub config: Account<'info, TransferHookConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct SetExemption<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
-    
+    #[account(seeds = [b"config", config.mint.as_ref()], bump, has_one = admin)]
+    pub config: Account<'info, TransferHookConfig>,
     #[account(
-        seeds = [b"config"],
+        init_if_needed,
+        payer = admin,
+        space = 8 + Exemption::INIT_SPACE,
+        seeds = [b"exempt", config.mint.as_ref(), key.as_ref()],
         bump
     )]
+    pub exemption: Account<'info, Exemption>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveExemption<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [b"config", config.mint.as_ref()], bump, has_one = admin)]
     pub config: Account<'info, TransferHookConfig>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"exempt", config.mint.as_ref(), exemption.key.as_ref()],
+        bump = exemption.bump
+    )]
+    pub exemption: Account<'info, Exemption>,
+}
 
+#[derive(Accounts)]
+pub struct InitHolderActivity<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Any wallet; only used as a seed
+    pub owner: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         init,
-        payer = admin,
-        space = Blacklist::SPACE,
-        seeds = [b"firewall-blacklist"],
+        payer = payer,
+        space = 8 + HolderActivity::INIT_SPACE,
+        seeds = [b"activity", mint.key().as_ref(), owner.key().as_ref()],
         bump
     )]
-    pub blacklist: Account<'info, Blacklist>,
+    pub holder_activity: Account<'info, HolderActivity>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ManageBlacklist<'info> {
+pub struct InitializeLaunchProtection<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
-    
+    #[account(seeds = [b"config", config.mint.as_ref()], bump, has_one = admin)]
+    pub config: Account<'info, TransferHookConfig>,
+    #[account(address = config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
-        seeds = [b"config"],
-        bump,
-        has_one = admin
+        init,
+        payer = admin,
+        space = 8 + LaunchProtection::INIT_SPACE,
+        seeds = [b"launch-protection", mint.key().as_ref()],
+        bump
     )]
-    pub config: Account<'info, TransferHookConfig>,
+    pub launch_protection: Account<'info, LaunchProtection>,
+    pub system_program: Program<'info, System>,
+}
 
+#[derive(Accounts)]
+pub struct UpdateLaunchProtection<'info> {
+    pub admin: Signer<'info>,
+    #[account(seeds = [b"config", config.mint.as_ref()], bump, has_one = admin)]
+    pub config: Account<'info, TransferHookConfig>,
     #[account(
         mut,
-        seeds = [b"firewall-blacklist"],
-        bump
+        seeds = [b"launch-protection", config.mint.as_ref()],
+        bump = launch_protection.bump
     )]
-    pub blacklist: Account<'info, Blacklist>,
+    pub launch_protection: Account<'info, LaunchProtection>,
 }
 
-// ============================================================================
-// STATE
-// ============================================================================
-
-#[account]
-pub struct Blacklist {
-    pub addresses: [Pubkey; 100],
-    pub count: u8,
-    pub bump: u8,
+#[derive(Accounts)]
+pub struct SetTransferFee<'info> {
+    pub admin: Signer<'info>,
+    #[account(seeds = [b"config", config.mint.as_ref()], bump, has_one = admin)]
+    pub config: Account<'info, TransferHookConfig>,
+    #[account(mut, address = config.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA set as the mint's transfer fee config authority
+    #[account(seeds = [b"fee-authority", mint.key().as_ref()], bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
 }
 
-impl Blacklist {
-    pub const SPACE: usize = 8 + (32 * 100) + 1 + 1;
+#[derive(Accounts)]
+pub struct HarvestFees<'info> {
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA set as the mint's withdraw withheld authority
+    #[account(seeds = [b"fee-authority", mint.key().as_ref()], bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+    /// CHECK: grid_fee_sweep vault authority
+    #[account(seeds = [b"fee-vault", mint.key().as_ref()], bump, seeds::program = FEE_SWEEP_PROGRAM)]
+    pub fee_vault_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fee_vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
 }
 
-#[account]
-#[derive(InitSpace)]
+#[derive(Accounts)]
+pub struct ManageBlocklist<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config", config.mint.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, TransferHookConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// STATE
+// ============================================================================
+
+/// Per-check exemption for a protocol owner or token account: [b"exempt", mint, key]
+#[account]
+#[derive(InitSpace)]
+pub struct Exemption {
+    pub mint: Pubkey,
+    /// Owner wallet or token account the exemption applies to
+    pub key: Pubkey,
+    pub flags: ExemptionFlags,
+    pub bump: u8,
+}
+
+/// Checks skipped when the key sends; only skip_receive_rules applies when it receives
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct ExemptionFlags {
+    /// Skip the Policy Guard CPI
+    pub skip_guard: bool,
+    /// Skip launch protection
+    pub skip_anti_snipe: bool,
+    /// Skip KYA-based hook rules
+    pub skip_kya: bool,
+    /// Skip per-holder velocity limits
+    pub skip_velocity: bool,
+    /// Keep sending in every HookMode
+    pub bypass_mode: bool,
+    /// Receive without meeting the receive rules (destination side)
+    pub skip_receive_rules: bool,
+}
+
+impl ExemptionFlags {
+    fn union(self, other: ExemptionFlags) -> ExemptionFlags {
+        ExemptionFlags {
+            skip_guard: self.skip_guard || other.skip_guard,
+            skip_anti_snipe: self.skip_anti_snipe || other.skip_anti_snipe,
+            skip_kya: self.skip_kya || other.skip_kya,
+            skip_velocity: self.skip_velocity || other.skip_velocity,
+            bypass_mode: self.bypass_mode || other.bypass_mode,
+            skip_receive_rules: self.skip_receive_rules || other.skip_receive_rules,
+        }
+    }
+}
+
+pub const MAX_LAUNCH_STAGES: usize = 4;
+pub const MAX_LAUNCH_EXEMPT: usize = 8;
+pub const MAX_LAUNCH_POOLS: usize = 4;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Anti-snipe rules for a mint's launch window: [b"launch-protection", mint]
+/// Every check switches off once `launch_ts + window_seconds` has passed.
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchProtection {
+    pub mint: Pubkey,
+    pub launch_ts: i64,
+    pub window_seconds: u32,
+    /// Max single transfer as bps of supply; the latest stage started applies
+    pub schedule: [LaunchStage; MAX_LAUNCH_STAGES],
+    /// Max balance of the destination token account as bps of supply (0 = off)
+    /// A per-account cap: one owner can still spread a position over
+    /// several token accounts.
+    pub max_account_balance_bps: u16,
+    /// Max tokens bought per slot, counting transfers out of `pools` (0 = off)
+    pub max_buy_per_slot: u64,
+    /// Owners exempt from every launch check (treasury, fee vault, ...)
+    /// Never list an AMM pool here (that would exempt every buy); pools go in `pools`.
+    pub exempt: [Pubkey; MAX_LAUNCH_EXEMPT],
+    /// AMM pool owners (authorities); transfers out of them are buys
+    pub pools: [Pubkey; MAX_LAUNCH_POOLS],
+    /// Slot tracked by `slot_volume`
+    pub current_slot: u64,
+    pub slot_volume: u64,
+    pub bump: u8,
+}
+
+/// One step of the max-transfer schedule (max_transfer_bps 0 = empty slot)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct LaunchStage {
+    /// Seconds after launch this stage takes effect
+    pub starts_after: u32,
+    pub max_transfer_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LaunchProtectionParams {
+    pub launch_ts: i64,
+    pub window_seconds: u32,
+    pub schedule: [LaunchStage; MAX_LAUNCH_STAGES],
+    pub max_account_balance_bps: u16,
+    pub max_buy_per_slot: u64,
+    pub exempt: [Pubkey; MAX_LAUNCH_EXEMPT],
+    pub pools: [Pubkey; MAX_LAUNCH_POOLS],
+}
+
+impl LaunchProtection {
+    fn apply(&mut self, params: LaunchProtectionParams) -> Result<()> {
+        // Used stages must come first, in increasing start order
+        let used = params.schedule.iter().take_while(|stage| stage.max_transfer_bps > 0).count();
+        require!(
+            params.schedule[used..].iter().all(|stage| stage.max_transfer_bps == 0)
+                && params.schedule[..used].windows(2).all(|w| w[0].starts_after < w[1].starts_after)
+                && params.schedule.iter().all(|stage| stage.max_transfer_bps as u64 <= BPS_DENOMINATOR)
+                && params.max_account_balance_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidLaunchProtection
+        );
+
+        self.launch_ts = params.launch_ts;
+        self.window_seconds = params.window_seconds;
+        self.schedule = params.schedule;
+        self.max_account_balance_bps = params.max_account_balance_bps;
+        self.max_buy_per_slot = params.max_buy_per_slot;
+        self.exempt = params.exempt;
+        self.pools = params.pools;
+        Ok(())
+    }
+
+    fn is_pool(&self, owner: &Pubkey) -> bool {
+        *owner != Pubkey::default() && self.pools.contains(owner)
+    }
+
+    /// Add a buy to the current slot's volume, starting over on a new slot
+    fn record_buy(&mut self, slot: u64, amount: u64) -> Result<()> {
+        if self.current_slot != slot {
+            self.current_slot = slot;
+            self.slot_volume = 0;
+        }
+        self.slot_volume = self.slot_volume
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(self.slot_volume <= self.max_buy_per_slot, ErrorCode::SlotBuyCapExceeded);
+        Ok(())
+    }
+
+    /// Max single transfer in bps of supply `elapsed` seconds after launch
+    fn max_transfer_bps(&self, elapsed: u64) -> Option<u16> {
+        self.schedule
+            .iter()
+            .take_while(|stage| stage.max_transfer_bps > 0)
+            .filter(|stage| stage.starts_after as u64 <= elapsed)
+            .last()
+            .map(|stage| stage.max_transfer_bps)
+    }
+}
+
+/// Per-holder transfer history: [b"activity", mint, owner]
+/// The window is split into VELOCITY_BUCKETS buckets, so it rolls forward
+/// one bucket (window / VELOCITY_BUCKETS) at a time.
+#[account]
+#[derive(InitSpace)]
+pub struct HolderActivity {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    /// Bucket length the buckets were recorded with
+    pub bucket_seconds: u32,
+    pub buckets: [ActivityBucket; VELOCITY_BUCKETS],
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct ActivityBucket {
+    /// unix_timestamp / bucket_seconds
+    pub epoch: u64,
+    pub transfers: u32,
+    pub amount: u64,
+}
+
+impl HolderActivity {
+    /// Add a transfer and return the window totals (transfers, amount)
+    fn record(&mut self, window_seconds: u32, amount: u64, now: i64) -> Result<(u32, u64)> {
+        let bucket_seconds = (window_seconds / VELOCITY_BUCKETS as u32).max(1);
+        if self.bucket_seconds != bucket_seconds {
+            self.bucket_seconds = bucket_seconds;
+            self.buckets = Default::default();
+        }
+
+        let epoch = now.max(0) as u64 / bucket_seconds as u64;
+        let bucket = &mut self.buckets[(epoch % VELOCITY_BUCKETS as u64) as usize];
+        if bucket.epoch != epoch {
+            *bucket = ActivityBucket { epoch, ..Default::default() };
+        }
+        bucket.transfers = bucket.transfers.saturating_add(1);
+        bucket.amount = bucket.amount.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        let oldest = epoch.saturating_sub(VELOCITY_BUCKETS as u64 - 1);
+        Ok(self.buckets
+            .iter()
+            .filter(|bucket| bucket.epoch >= oldest && bucket.epoch <= epoch)
+            .fold((0u32, 0u64), |(transfers, total), bucket| {
+                (transfers.saturating_add(bucket.transfers), total.saturating_add(bucket.amount))
+            }))
+    }
+}
+
+/// Entries per block/unblock instruction (bounded by transaction size)
+pub const MAX_BLOCK_BATCH: usize = 20;
+
+/// A blocked wallet, one PDA per mint and address: [b"blocked", mint, address]
+#[account]
+#[derive(InitSpace)]
+pub struct BlockedAddress {
+    pub mint: Pubkey,
+    pub address: Pubkey,
+    pub reason: BlockReason,
+    pub blocked_at: i64,
+    /// Block lapses after this timestamp (None = permanent)
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum BlockReason {
+    Sanctions,
+    Exploit,
+    Fraud,
+    CompromisedKey,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BlockEntry {
+    pub address: Pubkey,
+    pub reason: BlockReason,
+    pub expires_at: Option<i64>,
+}
+
+/// Per-mint hook config: [b"config", mint]
+#[account]
+#[derive(InitSpace)]
 pub struct TransferHookConfig {
     pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub agent_id_program: Pubkey,
+    pub policy_guard_program: Pubkey,
+    pub receive_rules: ReceiveRules,
+    pub transfer_tiers: TransferTiers,
+    pub velocity_limits: VelocityLimits,
+    pub mode: HookMode,
+    /// Can tighten `mode` instantly (Pubkey::default() = none)
+    pub guardian: Pubkey,
+    /// Policy Guard evaluation of transfers (off by default)
+    pub guard: GuardSettings,
+}
+
+/// Opt-in Policy Guard evaluation of a mint's transfers
+/// Every transfer is then an evaluation, so the guard's cooldown and
+/// valuation age gate all of them; meant for mints whose transfers are
+/// treasury operations.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct GuardSettings {
+    pub enabled: bool,
+    /// Pyth-layout feed quoting the mint in USD; the guard counts spend in
+    /// quote units, valued at the top of the confidence interval
+    pub price_feed: Pubkey,
+    pub max_staleness_seconds: u32,
+    pub max_confidence_bps: u16,
+}
+
+impl GuardSettings {
+    pub fn price_tolerance(&self) -> PriceTolerance {
+        PriceTolerance {
+            max_staleness_seconds: self.max_staleness_seconds,
+            max_confidence_bps: self.max_confidence_bps,
+        }
+    }
+}
+
+/// Transfer mode of a mint, from least to most restrictive
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, InitSpace)]
+pub enum HookMode {
+    #[default]
+    Normal,
+    /// Source must hold an active agent passport
+    AgentsOnly,
+    /// Destination must be allowlisted in the mint's Policy Guard listings
+    AllowlistOnly,
+    /// Only senders exempted with bypass_mode can transfer
+    Frozen,
+}
+
+/// Rolling per-holder limits (0 = no limit)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct VelocityLimits {
+    pub window_seconds: u32,
+    pub max_transfers: u32,
+    pub max_amount: u64,
+}
+
+impl VelocityLimits {
+    pub fn is_enabled(&self) -> bool {
+        self.max_transfers > 0 || self.max_amount > 0
+    }
+}
+
+/// KYA_SPEC value tiers: max single transfer per source KYA level
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct TransferTiers {
+    pub unit: TierUnit,
+    /// Max single transfer for KYA level 0..=3 (None = unlimited)
+    pub max_transfer: [Option<u64>; 4],
+    /// Pyth-layout feed quoting the mint in USD (Usd unit only)
+    pub price_feed: Pubkey,
+    pub max_staleness_seconds: u32,
+    pub max_confidence_bps: u16,
+}
+
+impl TransferTiers {
+    pub fn is_enabled(&self) -> bool {
+        self.max_transfer.iter().any(Option::is_some)
+    }
+
+    pub fn price_tolerance(&self) -> PriceTolerance {
+        PriceTolerance {
+            max_staleness_seconds: self.max_staleness_seconds,
+            max_confidence_bps: self.max_confidence_bps,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum TierUnit {
+    /// Limits in base units of the mint
+    #[default]
+    Tokens,
+    /// Limits in whole USD, priced via `price_feed`
+    Usd,
+}
+
+/// Receive-side KYA requirements for destination owners
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct ReceiveRules {
+    /// Minimum effective KYA level to receive (0 = anyone)
+    pub min_kya_level: u8,
+    /// Transfers above this amount need an active passport (0 = off)
+    pub passport_threshold: u64,
+    /// Suspended agents cannot receive
+    pub block_suspended: bool,
+}
+
+/// Effective KYA of a wallet, from its agent-id PDA
+#[derive(Clone, Copy, Debug)]
+pub struct AgentStanding {
+    pub level: u8,
+    /// Registered identity with an active subscription
+    pub passport: bool,
+    pub suspended: bool,
 }
 
 // ============================================================================
 // HELPERS & ERRORS
 // ============================================================================
 
-fn is_blacklisted(addresses: &[Pubkey; 100], check: &Pubkey) -> bool {
-    for addr in addresses.iter() {
-        if addr == check { return true; }
+/// The DAO admin may set any mode; the guardian may only move to a stricter one
+fn check_mode_change(config: &TransferHookConfig, authority: &Pubkey, mode: HookMode) -> Result<()> {
+    let is_admin = *authority == config.admin;
+    let is_guardian = config.guardian != Pubkey::default() && *authority == config.guardian;
+    require!(is_admin || is_guardian, ErrorCode::Unauthorized);
+    require!(is_admin || mode > config.mode, ErrorCode::ModeChangeRequiresDao);
+    Ok(())
+}
+
+fn validate_transfer_fee(fee_bps: u16, maximum_fee: u64) -> Result<()> {
+    require!(
+        (MIN_TRANSFER_FEE_BPS..=MAX_TRANSFER_FEE_BPS).contains(&fee_bps),
+        ErrorCode::TransferFeeOutOfBounds
+    );
+    require!(maximum_fee > 0, ErrorCode::TransferFeeOutOfBounds);
+    Ok(())
+}
+
+/// True if `entry` is an initialized, unexpired BlockedAddress
+/// Transfers pass the PDA whether or not it exists; a missing one is not blocked.
+fn is_blocked(entry: &AccountInfo, now: i64) -> Result<bool> {
+    if entry.owner != &crate::ID || entry.data_is_empty() {
+        return Ok(false);
+    }
+    let blocked = BlockedAddress::try_deserialize(&mut &entry.try_borrow_data()?[..])?;
+    Ok(blocked.expires_at.is_none_or(|expiry| now < expiry))
+}
+
+/// The hook's extra accounts, derived from config
+/// Shared by initialize/update so a rewritten list matches a fresh one.
+fn extra_account_metas(config: &TransferHookConfig) -> Result<[ExtraAccountMeta; EXTRA_ACCOUNT_METAS]> {
+    Ok([
+        // Account 6: Source Owner BlockedAddress (Extra Account Meta Index 0)
+        // Seeds: [b"blocked", mint, source_owner]; usually uninitialized
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"blocked".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 3 },
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        // Account 7: Policy Guard Program (Extra Account Meta Index 1)
+        ExtraAccountMeta::new_with_pubkey(
+            &config.policy_guard_program,
+            false, // is_signer
+            false, // is_writable
+        )?,
+        // Account 8: Policy Guard State (Extra Account Meta Index 2)
+        // Guard instance scoped to this mint: Policy Guard PDA [b"guard", mint]
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            7, // Policy Guard Program
+            &[
+                Seed::Literal { bytes: b"guard".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,                // is_signer
+            config.guard.enabled, // is_writable (evaluation updates the accumulators)
+        )?,
+        // Account 9: Agent Identity PDA (Extra Account Meta Index 3)
+        // Seeds: [b"agent-id", source_owner]
+        // Note: index 3 in transfer_hook refers to source_owner
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"agent-id".to_vec() },
+                Seed::AccountKey { index: 3 }, 
+            ],
+            false,
+            false,
+        )?,
+        // Account 10: Agent Identity Program (Extra Account Meta Index 4)
+        ExtraAccountMeta::new_with_pubkey(
+            &config.agent_id_program,
+            false,
+            false,
+        )?,
+        // Account 11: Instructions Sysvar (Extra Account Meta Index 5)
+        // Required by the Policy Guard for RULE_01 (vCPI prevention)
+        ExtraAccountMeta::new_with_pubkey(
+            &sysvar::instructions::ID,
+            false,
+            false,
+        )?,
+        // Account 12: Destination Owner Listing (Extra Account Meta Index 6)
+        // Policy Guard PDA [b"listing", guard_state, destination_token.owner]
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            7, // Policy Guard Program
+            &[
+                Seed::Literal { bytes: b"listing".to_vec() },
+                Seed::AccountKey { index: 8 },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Account 13: Destination Owner BlockedAddress (Extra Account Meta Index 7)
+        // Seeds: [b"blocked", mint, destination_token.owner]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"blocked".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Account 14: Launch Protection (Extra Account Meta Index 8)
+        // Seeds: [b"launch-protection", mint]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"launch-protection".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            true, // is_writable (per-slot buy volume)
+        )?,
+        // Accounts 15-18: Exemptions (Extra Account Meta Index 9-12)
+        // Seeds: [b"exempt", mint, key] for the source token account, source
+        // owner, destination token account and destination owner
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"exempt".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 0 },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"exempt".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 3 },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"exempt".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 2 },
+            ],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"exempt".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Account 19: Destination Agent Identity PDA (Extra Account Meta Index 13)
+        // Agent Identity PDA [b"agent-id", destination_token.owner]
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            10, // Agent Identity Program
+            &[
+                Seed::Literal { bytes: b"agent-id".to_vec() },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Account 20: Hook Config (Extra Account Meta Index 14)
+        // Seeds: [b"config", mint]; carries the mint's hook rules
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"config".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // Account 21: USD Price Feed for transfer tiers (Extra Account Meta Index 15)
+        ExtraAccountMeta::new_with_pubkey(
+            &config.transfer_tiers.price_feed,
+            false,
+            false,
+        )?,
+        // Account 22: Source Owner HolderActivity (Extra Account Meta Index 16)
+        // Seeds: [b"activity", mint, source_owner]; writable for velocity tracking
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"activity".to_vec() },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 3 },
+            ],
+            false,
+            true,
+        )?,
+        // Account 23: USD Price Feed for guard evaluations (Extra Account Meta Index 17)
+        ExtraAccountMeta::new_with_pubkey(
+            &config.guard.price_feed,
+            false,
+            false,
+        )?,
+    ])
+}
+
+/// Standing of `wallet`, or None if it has no identity
+/// Shared by the source and destination sides: an expired subscription counts
+/// as Level 1 and a suspended agent as Level 0 without a passport.
+fn agent_standing(identity: Option<&AgentIdentity>, wallet: &Pubkey, now: i64) -> Option<AgentStanding> {
+    let identity = identity.filter(|identity| identity.agent_wallet == *wallet)?;
+    if identity.suspended {
+        msg!("Transfer Hook: Agent {} is SUSPENDED. Treated as Level 0.", wallet);
+        return Some(AgentStanding { level: 0, passport: false, suspended: true });
+    }
+    if identity.subscription_expiry > now {
+        return Some(AgentStanding { level: identity.kya_level, passport: true, suspended: false });
+    }
+    msg!("Transfer Hook: Agent Subscription EXPIRED. Treated as Level 1.");
+    Some(AgentStanding { level: 1, passport: false, suspended: false })
+}
+
+/// Agent identity stored at `account`, or None if the PDA does not exist
+fn read_agent_identity(account: &AccountInfo, agent_id_program: &Pubkey) -> Result<Option<AgentIdentity>> {
+    if account.owner != agent_id_program || account.data_is_empty() {
+        return Ok(None);
     }
-    false
+    Ok(Some(AgentIdentity::load(&account.try_borrow_data()?)?))
+}
+
+fn enforce_receive_rules(
+    rules: &ReceiveRules,
+    standing: Option<AgentStanding>,
+    dest_owner: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let level = standing.map_or(0, |s| s.level);
+    if rules.block_suspended && standing.is_some_and(|s| s.suspended) {
+        msg!("Grid Firewall: BLOCKED - Destination {} is a suspended agent", dest_owner);
+        return Err(ErrorCode::RecipientSuspended.into());
+    }
+    if level < rules.min_kya_level {
+        msg!("Grid Firewall: BLOCKED - Destination {} is KYA {} (min {})", dest_owner, level, rules.min_kya_level);
+        return Err(ErrorCode::RecipientKyaTooLow.into());
+    }
+    if rules.passport_threshold > 0 && amount > rules.passport_threshold && !standing.is_some_and(|s| s.passport) {
+        msg!("Grid Firewall: BLOCKED - Destination {} needs a passport above {}", dest_owner, rules.passport_threshold);
+        return Err(ErrorCode::RecipientPassportRequired.into());
+    }
+    Ok(())
+}
+
+/// Reject transfers above the source's tier, naming the lowest tier that allows it
+fn enforce_transfer_tier(tiers: &TransferTiers, kya_level: u8, value: u64) -> Result<()> {
+    let allows = |tier: usize| tiers.max_transfer[tier].is_none_or(|max| value <= max);
+    let level = (kya_level as usize).min(tiers.max_transfer.len() - 1);
+    if allows(level) {
+        return Ok(());
+    }
+
+    let required = (level + 1..tiers.max_transfer.len()).find(|&tier| allows(tier));
+    msg!("Grid Firewall: BLOCKED - Transfer value {} exceeds KYA {} limit (requires {:?})", value, level, required);
+    Err(match required {
+        Some(1) => ErrorCode::KyaTier1Required,
+        Some(2) => ErrorCode::KyaTier2Required,
+        Some(3) => ErrorCode::KyaTier3Required,
+        _ => ErrorCode::TransferAboveAllTiers,
+    }
+    .into())
+}
+
+/// USD value of `amount` with `quote_decimals` decimals (0 = whole USD),
+/// priced at the top of the feed's confidence interval so limits err on
+/// the strict side. Parsing and checks are shared with the Policy Guard.
+fn usd_value(
+    feed: &AccountInfo,
+    tolerance: &PriceTolerance,
+    amount: u64,
+    decimals: u8,
+    quote_decimals: i32,
+    now: i64,
+) -> Result<u64> {
+    let price = PriceFeedData::parse(&feed.try_borrow_data()?).map_err(ErrorCode::from)?;
+    let unit_price = price.spend_price(tolerance, now).map_err(ErrorCode::from)?;
+    Ok(core::usd_value(amount, decimals, unit_price, price.expo, quote_decimals).map_err(|_| ErrorCode::Overflow)?)
+}
+
+/// Flags of an Exemption PDA, or none if it was never created
+fn read_exemption(entry: &AccountInfo) -> Result<ExemptionFlags> {
+    if entry.owner != &crate::ID || entry.data_is_empty() {
+        return Ok(ExemptionFlags::default());
+    }
+    Ok(Exemption::try_deserialize(&mut &entry.try_borrow_data()?[..])?.flags)
+}
+
+/// Union of the flags of one side's Exemption PDAs
+fn read_exemptions(entries: &[Option<&AccountInfo>]) -> Result<ExemptionFlags> {
+    entries.iter().flatten().try_fold(ExemptionFlags::default(), |flags, entry| {
+        Ok(flags.union(read_exemption(entry)?))
+    })
+}
+
+/// Whether the Policy Guard listing marks `dest_owner` as Allowed
+fn is_allowlisted(account: &AccountInfo, config: &TransferHookConfig, dest_owner: &Pubkey) -> Result<bool> {
+    if account.owner != &config.policy_guard_program || account.data_is_empty() {
+        return Ok(false);
+    }
+    let listing = CounterpartyListing::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    Ok(listing.address == *dest_owner && listing.listing == Listing::Allowed)
+}
+
+/// Apply the launch window rules; no-op if the PDA is missing or the window is over
+fn enforce_launch_protection(
+    account: &AccountInfo,
+    amount: u64,
+    source_owner: &Pubkey,
+    dest_owner: &Pubkey,
+    supply: u64,
+    destination_balance: u64,
+) -> Result<()> {
+    if account.owner != &crate::ID || account.data_is_empty() {
+        return Ok(());
+    }
+    let mut protection = LaunchProtection::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let clock = Clock::get()?;
+    let window_end = protection.launch_ts.saturating_add(protection.window_seconds as i64);
+    if clock.unix_timestamp >= window_end {
+        return Ok(());
+    }
+    let is_exempt = |key: &Pubkey| *key != Pubkey::default() && protection.exempt.contains(key);
+    if is_exempt(source_owner) || is_exempt(dest_owner) {
+        return Ok(());
+    }
+
+    let elapsed = clock.unix_timestamp.saturating_sub(protection.launch_ts).max(0) as u64;
+    if let Some(bps) = protection.max_transfer_bps(elapsed) {
+        let threshold = bps_of(supply, bps)?;
+        if amount > threshold {
+            msg!("Grid Firewall: ALERT - Large transfer {} (>{} bps of supply). ANTI-SNIPE ACTIVE.", amount, bps);
+            return Err(ErrorCode::AntiSnipeTriggered.into());
+        }
+    }
+
+    // The hook runs after the transfer, so this is the post-transfer balance
+    let balance_cap_bps = protection.max_account_balance_bps;
+    if balance_cap_bps > 0 && destination_balance > bps_of(supply, balance_cap_bps)? {
+        msg!("Grid Firewall: BLOCKED - {} token account would hold {} during launch", dest_owner, destination_balance);
+        return Err(ErrorCode::AccountBalanceCapExceeded.into());
+    }
+
+    // Only transfers out of a listed pool are buys
+    if protection.max_buy_per_slot > 0 && protection.is_pool(source_owner) {
+        protection.record_buy(clock.slot, amount)?;
+        protection.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    }
+    Ok(())
+}
+
+fn enforce_velocity_limits(
+    account: &AccountInfo,
+    limits: &VelocityLimits,
+    source_owner: &Pubkey,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    if account.owner != &crate::ID || account.data_is_empty() {
+        msg!("Grid Firewall: BLOCKED - {} has no holder activity; call init_holder_activity", source_owner);
+        return Err(ErrorCode::HolderActivityNotInitialized.into());
+    }
+    let mut activity = HolderActivity::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let (transfers, total) = activity.record(limits.window_seconds, amount, now)?;
+
+    if limits.max_transfers > 0 && transfers > limits.max_transfers {
+        msg!("Grid Firewall: BLOCKED - {} made {} transfers in {}s", source_owner, transfers, limits.window_seconds);
+        return Err(ErrorCode::TransferCountExceeded.into());
+    }
+    if limits.max_amount > 0 && total > limits.max_amount {
+        msg!("Grid Firewall: BLOCKED - {} sent {} in {}s", source_owner, total, limits.window_seconds);
+        return Err(ErrorCode::TransferVolumeExceeded.into());
+    }
+    activity.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Reject direct calls to Execute: Token-2022 sets `transferring` on the
+/// source account only while it invokes the hook for a real transfer
+fn assert_is_transferring(source_token: &AccountInfo) -> Result<()> {
+    let data = source_token.try_borrow_data()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), ErrorCode::NotTransferring);
+    Ok(())
+}
+
+fn bps_of(value: u64, bps: u16) -> Result<u64> {
+    Ok((value as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64)
+}
+
+/// Create a BlockedAddress PDA, tolerating lamports sent to it beforehand
+fn create_blocked_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let space = 8 + BlockedAddress::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    if account.lamports() == 0 {
+        return anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                anchor_lang::system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Allocate { account_to_allocate: account.clone() },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Assign { account_to_assign: account.clone() },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
 }
 
 #[error_code]
 pub enum ErrorCode {
     #[msg("Address is blacklisted by the Grid Firewall")]
     BlacklistedAddress,
-    #[msg("Blacklist is full (max 100 addresses)")]
-    BlacklistFull,
+    #[msg("Too many entries for one block/unblock batch")]
+    BatchTooLarge,
     #[msg("Math overflow occurred")]
     Overflow,
     #[msg("Anti-Snipe Protection Triggered: Transfer too large")]
     AntiSnipeTriggered,
     #[msg("Unauthorized access")]
     Unauthorized,
+    #[msg("Account is not the BlockedAddress PDA of the entry")]
+    BlockedAccountMismatch,
+    #[msg("Block expiry must be in the future")]
+    InvalidBlockExpiry,
+    #[msg("Transfer fee must be between 2% and 3% (at least a 1% burn) with a non-zero cap")]
+    TransferFeeOutOfBounds,
+    #[msg("Launch protection schedule or caps are invalid")]
+    InvalidLaunchProtection,
+    #[msg("Anti-Snipe Protection Triggered: Launch account balance cap exceeded")]
+    AccountBalanceCapExceeded,
+    #[msg("Anti-Snipe Protection Triggered: Per-slot buy cap reached")]
+    SlotBuyCapExceeded,
+    #[msg("Receive rules are invalid")]
+    InvalidReceiveRules,
+    #[msg("Recipient is a suspended agent")]
+    RecipientSuspended,
+    #[msg("Recipient KYA level is below the minimum to receive")]
+    RecipientKyaTooLow,
+    #[msg("Recipient needs an active passport for this amount")]
+    RecipientPassportRequired,
+    #[msg("Transfer tiers are invalid")]
+    InvalidTransferTiers,
+    #[msg("Transfer exceeds the sender's KYA tier: Tier 1 required")]
+    KyaTier1Required,
+    #[msg("Transfer exceeds the sender's KYA tier: Tier 2 required")]
+    KyaTier2Required,
+    #[msg("Transfer exceeds the sender's KYA tier: Tier 3 required")]
+    KyaTier3Required,
+    #[msg("Transfer exceeds the limit of every KYA tier")]
+    TransferAboveAllTiers,
+    #[msg("Price feed is not a valid trading price account")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale or too uncertain")]
+    PriceFeedStale,
+    #[msg("Velocity limits are invalid")]
+    InvalidVelocityLimits,
+    #[msg("Holder activity not initialized for the source owner")]
+    HolderActivityNotInitialized,
+    #[msg("Velocity Limit: Too many transfers in the window")]
+    TransferCountExceeded,
+    #[msg("Velocity Limit: Transfer volume exceeded for the window")]
+    TransferVolumeExceeded,
+    #[msg("ExtraAccountMetaList does not match config; call update_extra_account_meta_list")]
+    StaleExtraAccountMetaList,
+    #[msg("Kill Switch: Mint is frozen")]
+    HookFrozen,
+    #[msg("Kill Switch: AgentsOnly mode requires an active passport")]
+    PassportRequired,
+    #[msg("Kill Switch: AllowlistOnly mode requires an allowlisted destination")]
+    DestinationNotAllowlisted,
+    #[msg("Only the DAO can make this hook mode change")]
+    ModeChangeRequiresDao,
+    #[msg("Hook called outside of a Token-2022 transfer")]
+    NotTransferring,
+    #[msg("Policy Guard settings are invalid")]
+    InvalidGuardSettings,
+}
+
+impl From<core::PriceError> for ErrorCode {
+    fn from(error: core::PriceError) -> Self {
+        match error {
+            core::PriceError::InvalidFeed | core::PriceError::Unavailable => ErrorCode::InvalidPriceFeed,
+            core::PriceError::Stale | core::PriceError::ConfidenceTooWide => ErrorCode::PriceFeedStale,
+            core::PriceError::Overflow => ErrorCode::Overflow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiers(max_transfer: [Option<u64>; 4]) -> TransferTiers {
+        TransferTiers {
+            max_transfer,
+            max_staleness_seconds: 60,
+            max_confidence_bps: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn transfer_tier_allows_amounts_within_the_level() {
+        let tiers = tiers([Some(100), Some(1_000), None, None]);
+        assert!(enforce_transfer_tier(&tiers, 0, 100).is_ok());
+        assert!(enforce_transfer_tier(&tiers, 1, 1_000).is_ok());
+        assert!(enforce_transfer_tier(&tiers, 2, u64::MAX).is_ok());
+        // Levels above 3 use the top tier
+        assert!(enforce_transfer_tier(&tiers, 7, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn transfer_tier_names_the_lowest_sufficient_tier() {
+        let open_top = tiers([Some(100), Some(1_000), None, None]);
+        assert_eq!(enforce_transfer_tier(&open_top, 0, 101).unwrap_err(), ErrorCode::KyaTier1Required.into());
+        assert_eq!(enforce_transfer_tier(&open_top, 0, 1_001).unwrap_err(), ErrorCode::KyaTier2Required.into());
+        assert_eq!(enforce_transfer_tier(&open_top, 1, 1_001).unwrap_err(), ErrorCode::KyaTier2Required.into());
+
+        let capped = tiers([Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(enforce_transfer_tier(&capped, 2, 4).unwrap_err(), ErrorCode::KyaTier3Required.into());
+        assert_eq!(enforce_transfer_tier(&capped, 3, 5).unwrap_err(), ErrorCode::TransferAboveAllTiers.into());
+    }
+
+    fn activity() -> HolderActivity {
+        HolderActivity {
+            mint: Pubkey::default(),
+            owner: Pubkey::default(),
+            bucket_seconds: 0,
+            buckets: Default::default(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn velocity_window_rolls_one_bucket_at_a_time() {
+        // 600s window -> 100s buckets
+        let mut activity = activity();
+        assert_eq!(activity.record(600, 5, 1_000).unwrap(), (1, 5));
+        assert_eq!(activity.record(600, 5, 1_099).unwrap(), (2, 10));
+        // Epoch 15 still sees epoch 10
+        assert_eq!(activity.record(600, 5, 1_500).unwrap(), (3, 15));
+        // Epoch 16 drops epoch 10
+        assert_eq!(activity.record(600, 5, 1_600).unwrap(), (2, 10));
+    }
+
+    #[test]
+    fn velocity_bucket_is_reset_when_its_slot_is_reused() {
+        let mut activity = activity();
+        activity.record(600, 7, 1_000).unwrap();
+        // Epoch 16 maps to the same ring slot as epoch 10
+        assert_eq!(activity.record(600, 3, 1_600).unwrap(), (1, 3));
+        assert_eq!(activity.buckets[4].epoch, 16);
+    }
+
+    #[test]
+    fn velocity_window_change_clears_history() {
+        let mut activity = activity();
+        activity.record(600, 7, 1_000).unwrap();
+        assert_eq!(activity.record(1_200, 3, 1_000).unwrap(), (1, 3));
+        assert_eq!(activity.bucket_seconds, 200);
+    }
+
+    fn stage(starts_after: u32, max_transfer_bps: u16) -> LaunchStage {
+        LaunchStage { starts_after, max_transfer_bps }
+    }
+
+    fn apply_schedule(schedule: [LaunchStage; MAX_LAUNCH_STAGES]) -> Result<LaunchProtection> {
+        let mut protection = LaunchProtection {
+            mint: Pubkey::default(),
+            launch_ts: 0,
+            window_seconds: 0,
+            schedule: Default::default(),
+            max_account_balance_bps: 0,
+            max_buy_per_slot: 0,
+            exempt: [Pubkey::default(); MAX_LAUNCH_EXEMPT],
+            pools: [Pubkey::default(); MAX_LAUNCH_POOLS],
+            current_slot: 0,
+            slot_volume: 0,
+            bump: 0,
+        };
+        protection.apply(LaunchProtectionParams {
+            launch_ts: 0,
+            window_seconds: 3_600,
+            schedule,
+            max_account_balance_bps: 0,
+            max_buy_per_slot: 0,
+            exempt: [Pubkey::default(); MAX_LAUNCH_EXEMPT],
+            pools: [Pubkey::default(); MAX_LAUNCH_POOLS],
+        })?;
+        Ok(protection)
+    }
+
+    #[test]
+    fn launch_schedule_uses_the_latest_started_stage() {
+        let protection =
+            apply_schedule([stage(60, 100), stage(600, 500), stage(1_800, 1_000), LaunchStage::default()]).unwrap();
+        assert_eq!(protection.max_transfer_bps(0), None);
+        assert_eq!(protection.max_transfer_bps(60), Some(100));
+        assert_eq!(protection.max_transfer_bps(599), Some(100));
+        assert_eq!(protection.max_transfer_bps(600), Some(500));
+        assert_eq!(protection.max_transfer_bps(u64::MAX), Some(1_000));
+
+        let empty = apply_schedule(Default::default()).unwrap();
+        assert_eq!(empty.max_transfer_bps(1_000), None);
+    }
+
+    #[test]
+    fn launch_schedule_must_be_ordered_and_packed() {
+        let invalid = |schedule| apply_schedule(schedule).err().unwrap();
+        let expected: Error = ErrorCode::InvalidLaunchProtection.into();
+        // Start times must strictly increase
+        assert_eq!(invalid([stage(600, 100), stage(60, 500), LaunchStage::default(), LaunchStage::default()]), expected);
+        assert_eq!(invalid([stage(60, 100), stage(60, 500), LaunchStage::default(), LaunchStage::default()]), expected);
+        // No used stage after an empty one
+        assert_eq!(invalid([stage(0, 100), LaunchStage::default(), stage(600, 500), LaunchStage::default()]), expected);
+        // At most 100% of supply
+        assert_eq!(invalid([stage(0, 10_001), LaunchStage::default(), LaunchStage::default(), LaunchStage::default()]), expected);
+    }
+
+    #[test]
+    fn slot_buy_cap_counts_transfers_out_of_pools() {
+        let pool = Pubkey::new_unique();
+        let mut protection = apply_schedule(Default::default()).unwrap();
+        protection.max_buy_per_slot = 100;
+        protection.pools[0] = pool;
+
+        assert!(protection.is_pool(&pool));
+        assert!(!protection.is_pool(&Pubkey::new_unique()));
+        assert!(!protection.is_pool(&Pubkey::default()));
+
+        protection.record_buy(7, 60).unwrap();
+        protection.record_buy(7, 40).unwrap();
+        assert_eq!(protection.record_buy(7, 1).unwrap_err(), ErrorCode::SlotBuyCapExceeded.into());
+        // A new slot starts from zero
+        protection.record_buy(8, 100).unwrap();
+        assert_eq!(protection.slot_volume, 100);
+    }
+
+    fn feed_data(price: i64, conf: u64, expo: i32, publish_ts: i64) -> Vec<u8> {
+        let status = core::price_feed_layout::STATUS_TRADING;
+        PriceFeedData { price, conf, expo, status, publish_ts }.to_account_data().to_vec()
+    }
+
+    fn quote(mut data: Vec<u8>, amount: u64, decimals: u8, quote_decimals: i32, now: i64) -> Result<u64> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let feed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &key, false, 0);
+        let tolerance = PriceTolerance { max_staleness_seconds: 60, max_confidence_bps: 100 };
+        usd_value(&feed, &tolerance, amount, decimals, quote_decimals, now)
+    }
+
+    fn usd(data: Vec<u8>, amount: u64, decimals: u8, now: i64) -> Result<u64> {
+        quote(data, amount, decimals, 0, now)
+    }
+
+    #[test]
+    fn usd_value_scales_by_exponent_and_decimals() {
+        // 1.5 tokens (6 decimals) at $2.00000000
+        assert_eq!(usd(feed_data(200_000_000, 0, -8, 0), 1_500_000, 6, 0).unwrap(), 3);
+        // Sub-dollar values round down
+        assert_eq!(usd(feed_data(200_000_000, 0, -8, 0), 1, 6, 0).unwrap(), 0);
+        // Negative exponent, no decimals: 10 tokens at $2.50
+        assert_eq!(usd(feed_data(250, 0, -2, 0), 10, 0, 0).unwrap(), 25);
+        // Positive exponent: 3 tokens at 4 * 10^2
+        assert_eq!(usd(feed_data(4, 0, 2, 0), 3, 0, 0).unwrap(), 1_200);
+        // Values beyond u64 are rejected rather than wrapped
+        assert_eq!(usd(feed_data(i64::MAX, 0, 0, 0), u64::MAX, 0, 0).unwrap_err(), ErrorCode::Overflow.into());
+    }
+
+    #[test]
+    fn guard_value_is_in_quote_units() {
+        // 1.5 tokens (6 decimals) at $2.00000000 -> $3 with 6 decimals
+        assert_eq!(quote(feed_data(200_000_000, 0, -8, 0), 1_500_000, 6, QUOTE_DECIMALS, 0).unwrap(), 3_000_000);
+        // One base unit is still worth something in quote units
+        assert_eq!(quote(feed_data(200_000_000, 0, -8, 0), 1, 6, QUOTE_DECIMALS, 0).unwrap(), 2);
+    }
+
+    #[test]
+    fn usd_value_prices_at_the_top_of_the_confidence_interval() {
+        // $1.00 +/- $0.01 (100 bps, the configured maximum)
+        assert_eq!(usd(feed_data(100, 1, -2, 0), 100, 0, 0).unwrap(), 101);
+        assert_eq!(
+            usd(feed_data(100, 2, -2, 0), 100, 0, 0).unwrap_err(),
+            ErrorCode::PriceFeedStale.into()
+        );
+    }
+
+    #[test]
+    fn usd_value_rejects_stale_or_invalid_feeds() {
+        assert!(usd(feed_data(100, 0, -2, 0), 1, 0, 60).is_ok());
+        assert_eq!(
+            usd(feed_data(100, 0, -2, 0), 1, 0, 61).unwrap_err(),
+            ErrorCode::PriceFeedStale.into()
+        );
+        assert_eq!(
+            usd(feed_data(0, 0, -2, 0), 1, 0, 0).unwrap_err(),
+            ErrorCode::InvalidPriceFeed.into()
+        );
+        assert_eq!(usd(vec![0u8; 16], 1, 0, 0).unwrap_err(), ErrorCode::InvalidPriceFeed.into());
+    }
+
+    /// Backing storage for an AccountInfo owned by `owner`
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new<T: AccountSerialize>(owner: Pubkey, state: &T) -> Self {
+            let mut data = Vec::new();
+            state.try_serialize(&mut data).unwrap();
+            TestAccount { key: Pubkey::new_unique(), owner, lamports: 0, data }
+        }
+
+        fn uninitialized() -> Self {
+            TestAccount { key: Pubkey::new_unique(), owner: anchor_lang::system_program::ID, lamports: 0, data: Vec::new() }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(&self.key, false, false, &mut self.lamports, &mut self.data, &self.owner, false, 0)
+        }
+    }
+
+    fn config() -> TransferHookConfig {
+        TransferHookConfig {
+            admin: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            agent_id_program: Pubkey::new_unique(),
+            policy_guard_program: Pubkey::new_unique(),
+            receive_rules: ReceiveRules::default(),
+            transfer_tiers: TransferTiers::default(),
+            velocity_limits: VelocityLimits::default(),
+            mode: HookMode::Normal,
+            guardian: Pubkey::new_unique(),
+            guard: GuardSettings::default(),
+        }
+    }
+
+    #[test]
+    fn guardian_can_only_tighten_the_mode() {
+        let mut config = config();
+        let guardian = config.guardian;
+        let modes = [HookMode::Normal, HookMode::AgentsOnly, HookMode::AllowlistOnly, HookMode::Frozen];
+
+        for from in modes {
+            config.mode = from;
+            for to in modes {
+                let result = check_mode_change(&config, &guardian, to);
+                if to > from {
+                    assert!(result.is_ok(), "{:?} -> {:?}", from, to);
+                } else {
+                    assert_eq!(result.unwrap_err(), ErrorCode::ModeChangeRequiresDao.into(), "{:?} -> {:?}", from, to);
+                }
+                // The DAO admin can move in either direction
+                assert!(check_mode_change(&config, &config.admin, to).is_ok());
+            }
+        }
+
+        assert_eq!(
+            check_mode_change(&config, &Pubkey::new_unique(), HookMode::Frozen).unwrap_err(),
+            ErrorCode::Unauthorized.into()
+        );
+        // An unset guardian cannot be impersonated with the default key
+        config.guardian = Pubkey::default();
+        config.mode = HookMode::Normal;
+        assert_eq!(
+            check_mode_change(&config, &Pubkey::default(), HookMode::Frozen).unwrap_err(),
+            ErrorCode::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn blocked_entries_lapse_at_expiry() {
+        let entry = |expires_at| BlockedAddress {
+            mint: Pubkey::new_unique(),
+            address: Pubkey::new_unique(),
+            reason: BlockReason::Sanctions,
+            blocked_at: 0,
+            expires_at,
+            bump: 0,
+        };
+
+        let mut permanent = TestAccount::new(crate::ID, &entry(None));
+        assert!(is_blocked(&permanent.info(), i64::MAX).unwrap());
+
+        let mut expiring = TestAccount::new(crate::ID, &entry(Some(100)));
+        assert!(is_blocked(&expiring.info(), 99).unwrap());
+        assert!(!is_blocked(&expiring.info(), 100).unwrap());
+
+        // A missing PDA, or one this program does not own, blocks nothing
+        assert!(!is_blocked(&TestAccount::uninitialized().info(), 0).unwrap());
+        let mut foreign = TestAccount::new(Pubkey::new_unique(), &entry(None));
+        assert!(!is_blocked(&foreign.info(), 0).unwrap());
+    }
+
+    #[test]
+    fn transfer_fee_stays_within_bounds() {
+        assert!(validate_transfer_fee(MIN_TRANSFER_FEE_BPS, 1).is_ok());
+        assert!(validate_transfer_fee(MAX_TRANSFER_FEE_BPS, u64::MAX).is_ok());
+        for (fee_bps, maximum_fee) in [(MIN_TRANSFER_FEE_BPS - 1, 1), (MAX_TRANSFER_FEE_BPS + 1, 1), (DEFAULT_TRANSFER_FEE_BPS, 0)] {
+            assert_eq!(
+                validate_transfer_fee(fee_bps, maximum_fee).unwrap_err(),
+                ErrorCode::TransferFeeOutOfBounds.into()
+            );
+        }
+    }
+
+    #[test]
+    fn exemptions_are_the_union_of_one_side() {
+        let exemption = |flags| Exemption { mint: Pubkey::new_unique(), key: Pubkey::new_unique(), flags, bump: 0 };
+        let mut token = TestAccount::new(crate::ID, &exemption(ExemptionFlags { skip_guard: true, ..Default::default() }));
+        let mut owner = TestAccount::new(crate::ID, &exemption(ExemptionFlags { skip_velocity: true, ..Default::default() }));
+        let mut missing = TestAccount::uninitialized();
+
+        let (token, owner, missing) = (token.info(), owner.info(), missing.info());
+        assert_eq!(
+            read_exemptions(&[Some(&token), Some(&owner), Some(&missing), None]).unwrap(),
+            ExemptionFlags { skip_guard: true, skip_velocity: true, ..Default::default() }
+        );
+        assert_eq!(read_exemptions(&[Some(&missing), None]).unwrap(), ExemptionFlags::default());
+    }
+
+    fn identity(wallet: Pubkey, kya_level: u8, subscription_expiry: i64, suspended: bool) -> AgentIdentity {
+        AgentIdentity {
+            authority: wallet,
+            agent_wallet: wallet,
+            name: String::new(),
+            capabilities: 0,
+            reputation_score: 0,
+            kya_level,
+            created_at: 0,
+            last_active: 0,
+            task_count: 0,
+            bump: 0,
+            subscription_expiry,
+            suspended,
+        }
+    }
+
+    #[test]
+    fn agent_standing_reflects_subscription_and_suspension() {
+        let wallet = Pubkey::new_unique();
+        let now = 1_000;
+
+        let active = agent_standing(Some(&identity(wallet, 3, now + 1, false)), &wallet, now).unwrap();
+        assert_eq!((active.level, active.passport, active.suspended), (3, true, false));
+        let expired = agent_standing(Some(&identity(wallet, 3, now, false)), &wallet, now).unwrap();
+        assert_eq!((expired.level, expired.passport, expired.suspended), (1, false, false));
+        let suspended = agent_standing(Some(&identity(wallet, 3, now + 1, true)), &wallet, now).unwrap();
+        assert_eq!((suspended.level, suspended.passport, suspended.suspended), (0, false, true));
+
+        // An identity registered to another wallet does not count
+        assert!(agent_standing(Some(&identity(Pubkey::new_unique(), 3, now + 1, false)), &wallet, now).is_none());
+        assert!(agent_standing(None, &wallet, now).is_none());
+    }
+
+    #[test]
+    fn receive_rules_gate_the_destination() {
+        let dest = Pubkey::new_unique();
+        let rules = ReceiveRules { min_kya_level: 1, passport_threshold: 1_000, block_suspended: true };
+        let passport = AgentStanding { level: 2, passport: true, suspended: false };
+        let expired = AgentStanding { level: 1, passport: false, suspended: false };
+        let suspended = AgentStanding { level: 0, passport: false, suspended: true };
+
+        assert!(enforce_receive_rules(&rules, Some(passport), &dest, 5_000).is_ok());
+        assert!(enforce_receive_rules(&rules, Some(expired), &dest, 1_000).is_ok());
+        assert_eq!(
+            enforce_receive_rules(&rules, Some(expired), &dest, 1_001).unwrap_err(),
+            ErrorCode::RecipientPassportRequired.into()
+        );
+        assert_eq!(enforce_receive_rules(&rules, None, &dest, 1).unwrap_err(), ErrorCode::RecipientKyaTooLow.into());
+        assert_eq!(
+            enforce_receive_rules(&rules, Some(suspended), &dest, 1).unwrap_err(),
+            ErrorCode::RecipientSuspended.into()
+        );
+
+        // Default rules let anyone receive
+        assert!(enforce_receive_rules(&ReceiveRules::default(), None, &dest, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn extra_account_metas_follow_the_config() {
+        let mut config = config();
+        config.guard.price_feed = Pubkey::new_unique();
+        let metas = extra_account_metas(&config).unwrap();
+        assert_eq!(metas.len(), EXTRA_ACCOUNT_METAS);
+
+        // Index 1: Policy Guard Program, 4: Agent Identity Program, 17: guard price feed
+        assert_eq!(metas[1].address_config, config.policy_guard_program.to_bytes());
+        assert_eq!(metas[4].address_config, config.agent_id_program.to_bytes());
+        assert_eq!(metas[17].address_config, config.guard.price_feed.to_bytes());
+        // The guard state is only written while the guard is enabled
+        assert!(!bool::from(metas[2].is_writable));
+        config.guard.enabled = true;
+        assert!(bool::from(extra_account_metas(&config).unwrap()[2].is_writable));
+
+        // A config change rewrites to a different list
+        let mut moved = config.clone();
+        moved.policy_guard_program = Pubkey::new_unique();
+        assert_ne!(extra_account_metas(&moved).unwrap()[1], extra_account_metas(&config).unwrap()[1]);
+    }
+
+    #[test]
+    fn allowlist_is_read_from_the_mints_own_guard() {
+        let config = config();
+        let dest = Pubkey::new_unique();
+        let listing = |address, listing| CounterpartyListing {
+            guard: Pubkey::new_unique(),
+            address,
+            listing,
+            updated_ts: 0,
+            bump: 0,
+        };
+
+        let mut allowed = TestAccount::new(config.policy_guard_program, &listing(dest, Listing::Allowed));
+        assert!(is_allowlisted(&allowed.info(), &config, &dest).unwrap());
+        assert!(!is_allowlisted(&allowed.info(), &config, &Pubkey::new_unique()).unwrap());
+
+        let mut denied = TestAccount::new(config.policy_guard_program, &listing(dest, Listing::Denied));
+        assert!(!is_allowlisted(&denied.info(), &config, &dest).unwrap());
+        // Listings of another mint's guard program do not apply
+        let mut foreign = TestAccount::new(Pubkey::new_unique(), &listing(dest, Listing::Allowed));
+        assert!(!is_allowlisted(&foreign.info(), &config, &dest).unwrap());
+    }
 }
@@ -7,6 +7,7 @@ import {
   createAccount,
   mintTo,
   getAccount,
+  getExtraAccountMetas,
   transferChecked,
 } from "@solana/spl-token";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
@@ -27,6 +28,11 @@ describe("grid-transfer-hook", () => {
   let opsTokenAccount: PublicKey;
   let burnTokenAccount: PublicKey;
   let extraAccountMetaListPDA: PublicKey;
+  let configPDA: PublicKey;
+
+  // Programs the ExtraAccountMetaList resolves against
+  const agentIdProgram = new PublicKey("DDira32YctG7h2NW2L3Tt96bNuEVAsNyUxncKrTuz7QH");
+  const policyGuardProgram = new PublicKey("2u4LtXMzdttMEV54jFEWnQrvCbx3TguyvWyfdho7MFRW");
 
   const mintAuthority = Keypair.generate();
   const source = Keypair.generate();
@@ -38,6 +44,7 @@ describe("grid-transfer-hook", () => {
     await connection.requestAirdrop(source.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
     await connection.requestAirdrop(destination.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
     await connection.requestAirdrop(opsWallet.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
+    await connection.requestAirdrop(mintAuthority.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
     
     // Wait for airdrops
     await new Promise(resolve => setTimeout(resolve, 2000));
@@ -59,6 +66,29 @@ describe("grid-transfer-hook", () => {
     console.log("Mint created:", mint.toString());
   });
 
+  it("Initializes the per-mint config", async () => {
+    [configPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), mint.toBuffer()],
+      program.programId
+    );
+
+    // Created by the mint authority, which becomes the config admin
+    await program.methods
+      .initializeConfig(agentIdProgram, policyGuardProgram)
+      .accounts({
+        admin: mintAuthority.publicKey,
+        mint: mint,
+        config: configPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([mintAuthority])
+      .rpc();
+
+    const config = await program.account.transferHookConfig.fetch(configPDA);
+    expect(config.admin.toString()).to.equal(mintAuthority.publicKey.toString());
+    expect(config.policyGuardProgram.toString()).to.equal(policyGuardProgram.toString());
+  });
+
   it("Initializes ExtraAccountMetaList", async () => {
     // Derive PDA
     [extraAccountMetaListPDA] = PublicKey.findProgramAddressSync(
@@ -66,12 +96,13 @@ describe("grid-transfer-hook", () => {
       program.programId
     );
 
-    // Initialize
+    // Initialize; the list is written from the config
     await program.methods
       .initializeExtraAccountMetaList()
       .accounts({
         payer: wallet.publicKey,
         extraAccountMetaList: extraAccountMetaListPDA,
+        config: configPDA,
         mint: mint,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
@@ -79,9 +110,90 @@ describe("grid-transfer-hook", () => {
       })
       .rpc();
 
+    const list = await connection.getAccountInfo(extraAccountMetaListPDA);
+    const metas = getExtraAccountMetas(list!);
+    // Index 1: Policy Guard Program, index 4: Agent Identity Program
+    expect(new PublicKey(metas[1].addressConfig).toString()).to.equal(policyGuardProgram.toString());
+    expect(new PublicKey(metas[4].addressConfig).toString()).to.equal(agentIdProgram.toString());
+
     console.log("ExtraAccountMetaList initialized");
   });
 
+  it("Rewrites a stale ExtraAccountMetaList after a config change", async () => {
+    const newPolicyGuardProgram = Keypair.generate().publicKey;
+
+    // Changing a program id leaves the list pointing at the old one
+    await program.methods
+      .updateConfig(null, null, newPolicyGuardProgram)
+      .accounts({
+        admin: mintAuthority.publicKey,
+        config: configPDA,
+      })
+      .signers([mintAuthority])
+      .rpc();
+
+    let metas = getExtraAccountMetas((await connection.getAccountInfo(extraAccountMetaListPDA))!);
+    expect(new PublicKey(metas[1].addressConfig).toString()).to.equal(policyGuardProgram.toString());
+
+    // Only the mint authority or the config admin may rewrite it
+    const stranger = Keypair.generate();
+    await connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await new Promise(resolve => setTimeout(resolve, 1000));
+    try {
+      await program.methods
+        .updateExtraAccountMetaList()
+        .accounts({
+          authority: stranger.publicKey,
+          extraAccountMetaList: extraAccountMetaListPDA,
+          config: configPDA,
+          mint: mint,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+      expect.fail("stale list rewritten by a stranger");
+    } catch (err) {
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await program.methods
+      .updateExtraAccountMetaList()
+      .accounts({
+        authority: mintAuthority.publicKey,
+        extraAccountMetaList: extraAccountMetaListPDA,
+        config: configPDA,
+        mint: mint,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([mintAuthority])
+      .rpc();
+
+    metas = getExtraAccountMetas((await connection.getAccountInfo(extraAccountMetaListPDA))!);
+    expect(new PublicKey(metas[1].addressConfig).toString()).to.equal(newPolicyGuardProgram.toString());
+    expect(new PublicKey(metas[4].addressConfig).toString()).to.equal(agentIdProgram.toString());
+
+    // Restore the original program for the transfers below
+    await program.methods
+      .updateConfig(null, null, policyGuardProgram)
+      .accounts({
+        admin: mintAuthority.publicKey,
+        config: configPDA,
+      })
+      .signers([mintAuthority])
+      .rpc();
+    await program.methods
+      .updateExtraAccountMetaList()
+      .accounts({
+        authority: mintAuthority.publicKey,
+        extraAccountMetaList: extraAccountMetaListPDA,
+        config: configPDA,
+        mint: mint,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([mintAuthority])
+      .rpc();
+  });
+
   it("Creates token accounts", async () => {
     // Create source account
     sourceTokenAccount = await createAccount(